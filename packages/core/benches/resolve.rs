@@ -0,0 +1,152 @@
+//! Resolution of created components under contention.
+//!
+//! `baseline` is the former container (a `RwLock` around the component map), copied in `baseline/mod.rs`.
+//! `resolve_with_writer` resolves while another thread keeps putting a component, e.g. racing creations.
+
+mod baseline;
+
+use std::{
+    hint::black_box,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Barrier,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use portaldi_core::{container::DIContainer, traits::DIPortal, types::DI};
+
+struct Hoge;
+impl DIPortal for Hoge {
+    fn create_for_di(_container: &DIContainer) -> Self {
+        Hoge
+    }
+}
+
+/// Run `f` `iters` times on each of `threads` threads, and returns the elapsed time until all finish.
+fn run_contended(threads: usize, iters: u64, f: impl Fn() + Sync) -> Duration {
+    let barrier = Barrier::new(threads + 1);
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                barrier.wait();
+                for _ in 0..iters {
+                    f();
+                }
+            });
+        }
+        barrier.wait();
+        let start = Instant::now();
+        // the scope joins all threads.
+        start
+    })
+    .elapsed()
+}
+
+/// Like `run_contended`, while another thread keeps calling `write` until all threads finish.
+fn run_contended_with_writer(
+    threads: usize,
+    iters: u64,
+    f: impl Fn() + Sync,
+    write: impl Fn() + Sync,
+) -> Duration {
+    let done = AtomicBool::new(false);
+    thread::scope(|s| {
+        s.spawn(|| {
+            while !done.load(Ordering::Relaxed) {
+                write();
+            }
+        });
+        let elapsed = run_contended(threads, iters, f);
+        done.store(true, Ordering::Relaxed);
+        elapsed
+    })
+}
+
+fn bench_resolve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resolve");
+    for threads in [1, 4, 16] {
+        let container = DI::new(DIContainer::new());
+        Hoge::di_on(&container);
+        group.bench_with_input(
+            BenchmarkId::new("container", threads),
+            &threads,
+            |b, &threads| {
+                b.iter_custom(|iters| {
+                    run_contended(threads, iters, || {
+                        black_box(Hoge::di_on(&container));
+                    })
+                })
+            },
+        );
+
+        // the former `Hoge::di_on`.
+        let container = baseline::DIContainer::new();
+        container.get_or_init(|| Hoge);
+        group.bench_with_input(
+            BenchmarkId::new("baseline", threads),
+            &threads,
+            |b, &threads| {
+                b.iter_custom(|iters| {
+                    run_contended(threads, iters, || {
+                        black_box(container.get_or_init(|| Hoge));
+                    })
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_resolve_with_writer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resolve_with_writer");
+    for threads in [1, 4, 16] {
+        let container = DI::new(DIContainer::new());
+        let hoge = Hoge::di_on(&container);
+        group.bench_with_input(
+            BenchmarkId::new("container", threads),
+            &threads,
+            |b, &threads| {
+                b.iter_custom(|iters| {
+                    run_contended_with_writer(
+                        threads,
+                        iters,
+                        || {
+                            black_box(Hoge::di_on(&container));
+                        },
+                        || {
+                            black_box(container.put_if_absent(&hoge));
+                        },
+                    )
+                })
+            },
+        );
+
+        let container = baseline::DIContainer::new();
+        let hoge = container.get_or_init(|| Hoge);
+        group.bench_with_input(
+            BenchmarkId::new("baseline", threads),
+            &threads,
+            |b, &threads| {
+                b.iter_custom(|iters| {
+                    run_contended_with_writer(
+                        threads,
+                        iters,
+                        || {
+                            black_box(container.get_or_init(|| Hoge));
+                        },
+                        || {
+                            black_box(container.put_if_absent(&hoge));
+                        },
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_resolve, bench_resolve_with_writer);
+criterion_main!(benches);
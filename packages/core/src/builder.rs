@@ -0,0 +1,112 @@
+//! Build a container with pre-registered components.
+
+use std::{future::Future, pin::Pin};
+
+use crate::{
+    container::DIContainer,
+    traits::{AsyncDIPortal, DIPortal, DITarget},
+    types::DI,
+};
+
+/// A boxed future of async factories.
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+#[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// Conversion of a component into a target type, e.g. `DI<Foo>` into `DI<dyn FooI>`.
+///
+/// Macros implement this for each trait a provider is generated for, e.g. by `#[provide(FooI)]` or `bind!(dyn FooI => Foo)`.
+pub trait IntoTarget<T: ?Sized> {
+    fn into_target(self: DI<Self>) -> DI<T>;
+}
+
+/// Builder of a container with pre-registered components. (see [`DIContainer::builder`])
+///
+/// Registered components take precedence over providers generated by macros,
+/// and are singletons in the built container.
+///
+/// ```ignore
+/// let container = DIContainer::builder()
+///     .instance(Config::load())
+///     .bind::<dyn FooI, Foo>()
+///     .bind_with::<dyn QuxI, Qux>(|c| c)
+///     .factory::<dyn BarI>(|c| Bar::di_on(c))
+///     .async_factory::<dyn BazI>(|c| Box::pin(async move { Baz::di_on(c).await as DI<dyn BazI> }))
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct DIContainerBuilder {
+    container: DIContainer,
+}
+
+impl DIContainerBuilder {
+    /// Register a ready-made instance, replacing the previous instance (or registration) of the type.
+    ///
+    /// The instance is also registered, so that it's resolved again after [`DIContainer::dispose`].
+    pub fn instance<T: DITarget>(self, c: T) -> Self {
+        let c = DI::new(c);
+        self.container.put(&c);
+        self.factory::<T>(move |_| c.clone())
+    }
+
+    /// Bind a target type to an implementation, which is resolved through its [`DIPortal`].
+    pub fn bind<T, U>(self) -> Self
+    where
+        T: ?Sized + DITarget,
+        U: DIPortal + IntoTarget<T> + DITarget,
+    {
+        self.bind_with::<T, U>(U::into_target)
+    }
+
+    /// Bind a target type to an implementation, which is resolved through its [`AsyncDIPortal`].
+    pub fn bind_async<T, U>(self) -> Self
+    where
+        T: ?Sized + DITarget,
+        U: AsyncDIPortal + IntoTarget<T> + DITarget,
+    {
+        self.bind_async_with::<T, U>(U::into_target)
+    }
+
+    /// Bind a target type to an implementation with a conversion,
+    /// which is usually a coercion (`|c| c`) for an implementation without a provider of the target type.
+    pub fn bind_with<T, U>(self, into: fn(DI<U>) -> DI<T>) -> Self
+    where
+        T: ?Sized + DITarget,
+        U: DIPortal + DITarget,
+    {
+        self.factory::<T>(move |c| into(U::di_on(c)))
+    }
+
+    /// Bind a target type to an async implementation with a conversion. (see [`DIContainerBuilder::bind_with`])
+    pub fn bind_async_with<T, U>(self, into: fn(DI<U>) -> DI<T>) -> Self
+    where
+        T: ?Sized + DITarget,
+        U: AsyncDIPortal + DITarget,
+    {
+        self.async_factory::<T>(move |c| Box::pin(async move { into(U::di_on(c).await) }))
+    }
+
+    /// Register a factory of a target type.
+    pub fn factory<T: ?Sized + DITarget>(
+        self,
+        f: impl Fn(&DIContainer) -> DI<T> + DITarget,
+    ) -> Self {
+        self.container.register(f);
+        self
+    }
+
+    /// Register an async factory of a target type.
+    pub fn async_factory<T: ?Sized + DITarget>(
+        self,
+        f: impl for<'a> Fn(&'a DIContainer) -> BoxFuture<'a, DI<T>> + DITarget,
+    ) -> Self {
+        self.container.register_async(f);
+        self
+    }
+
+    /// Build a container.
+    pub fn build(self) -> DIContainer {
+        self.container
+    }
+}
@@ -1,21 +1,229 @@
 //! DI container functionality.
+//!
+//! In non Wasm target, components are held in an immutable map that is swapped atomically on writes,
+//! so that reads of created components are lock-free.
+//! Writes are rare (once per component). A new component is added to a table of recent components,
+//! which is merged into a new copy of the map when it is as large as the map, so that warm-up isn't quadratic.
+//!
+//! Components are keyed by `TypeId`, and their type names are kept only for diagnostics.
+//!
+//! A panic in component creation leaves no entry, and the component is created again on the next resolution.
 
-use crate::{traits::DITarget, types::DI};
-use std::{any::Any, collections::HashMap, future::Future};
+use crate::{
+    builder::{BoxFuture, DIContainerBuilder},
+    current::{current_container, replaced_global},
+    flight::{Flights, Role},
+    globals::INSTANCE,
+    named::NamedBinding,
+    registry::{Registered, Registration, Registry},
+    singleton,
+    traits::DITarget,
+    types::DI,
+    unwind::{self, create, create_async},
+};
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    fmt,
+    future::Future,
+    hash::{BuildHasherDefault, Hasher},
+};
 
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+use arc_swap::ArcSwap;
 #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
 use std::cell::RefCell;
 #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
-use std::sync::RwLock;
+use std::{
+    hash::BuildHasher,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, OnceLock,
+    },
+};
+
+#[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+pub(crate) type AnyDI = DI<dyn Any>;
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+pub(crate) type AnyDI = DI<dyn Any + Send + Sync>;
+
+/// A component with its type name.
+#[derive(Clone)]
+struct Component {
+    type_name: &'static str,
+    value: AnyDI,
+}
+
+impl Component {
+    fn new<T: DITarget>(value: DI<T>) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            value,
+        }
+    }
+
+    fn downcast<T: DITarget>(&self) -> DI<T> {
+        // keyed by `TypeId`, so the type always matches.
+        self.value.clone().downcast::<T>().unwrap()
+    }
+}
+
+impl fmt::Debug for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.type_name)
+    }
+}
+
+/// Hasher for `TypeId`, which is already a hash.
+#[derive(Default)]
+pub(crate) struct TypeIdHasher(u64);
+
+impl Hasher for TypeIdHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        // `TypeId` writes by `write_u64` or `write_u128` currently, but fold other writes just in case.
+        for b in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(*b);
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 ^= n;
+    }
+
+    fn write_u128(&mut self, n: u128) {
+        self.0 ^= n as u64 ^ (n >> 64) as u64;
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+type Components = HashMap<TypeId, Component, BuildHasherDefault<TypeIdHasher>>;
+
+/// Components of a thread-safe container, which are read lock-free.
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+#[derive(Debug, Default)]
+struct Snapshot {
+    map: Components,
+    /// Components added after `map` is copied.
+    recent: Recent,
+}
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+impl Snapshot {
+    fn get(&self, id: &TypeId) -> Option<&Component> {
+        self.map.get(id).or_else(|| self.recent.get(id))
+    }
+
+    /// Add a component to recent components, or return a new snapshot with it
+    /// if they are full or the type already has a component.
+    fn insert(&self, id: TypeId, c: Component) -> Option<Snapshot> {
+        if !self.map.contains_key(&id) && self.recent.try_insert(id, &c) {
+            return None;
+        }
+        let mut map = self.map.clone();
+        map.extend(self.recent.iter().map(|(id, c)| (*id, c.clone())));
+        map.insert(id, c);
+        Some(Snapshot {
+            recent: Recent::with_capacity(map.len()),
+            map,
+        })
+    }
+}
+
+/// An append-only hash table of recent components, whose slots are written once under the write lock.
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+#[derive(Default)]
+struct Recent {
+    slots: Box<[OnceLock<(TypeId, Component)>]>,
+    len: AtomicUsize,
+}
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+impl Recent {
+    /// A table of at least 8 components, which is at most half full for short probes.
+    fn with_capacity(capacity: usize) -> Self {
+        let slots = (capacity.max(8) * 2).next_power_of_two();
+        Recent {
+            slots: (0..slots).map(|_| OnceLock::new()).collect(),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Slots from the hashed position of a type, by linear probing.
+    fn probe(&self, id: &TypeId) -> impl Iterator<Item = &OnceLock<(TypeId, Component)>> {
+        let mask = self.slots.len().wrapping_sub(1);
+        let start = BuildHasherDefault::<TypeIdHasher>::default().hash_one(id) as usize;
+        (0..self.slots.len()).map(move |i| &self.slots[start.wrapping_add(i) & mask])
+    }
+
+    fn get(&self, id: &TypeId) -> Option<&Component> {
+        for slot in self.probe(id) {
+            match slot.get() {
+                Some((k, c)) if k == id => return Some(c),
+                Some(_) => {}
+                None => return None,
+            }
+        }
+        None
+    }
+
+    /// Insert a component unless the table is full or has the type. This is called under the write lock.
+    fn try_insert(&self, id: TypeId, c: &Component) -> bool {
+        // only written under the write lock.
+        if self.len.load(Ordering::Relaxed) >= self.slots.len() / 2 {
+            return false;
+        }
+        for slot in self.probe(&id) {
+            match slot.get() {
+                Some((k, _)) if *k == id => return false,
+                Some(_) => {}
+                None => {
+                    let _ = slot.set((id, c.clone()));
+                    self.len.fetch_add(1, Ordering::Relaxed);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &(TypeId, Component)> {
+        self.slots.iter().filter_map(OnceLock::get)
+    }
+}
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+impl fmt::Debug for Recent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter().map(|(_, c)| c)).finish()
+    }
+}
 
 /// DI container holds component refs.
 #[derive(Debug)]
 pub struct DIContainer {
-    /// Hold components by its type name (FQTN).
+    /// Hold components by its type.
     #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
-    components: RefCell<HashMap<String, DI<dyn Any>>>,
+    components: RefCell<Components>,
+    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    components: ArcSwap<Snapshot>,
+    /// Serialize writes of components.
     #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
-    components: RwLock<HashMap<String, DI<dyn Any + Send + Sync>>>,
+    write_lock: Mutex<()>,
+    /// Parent container of a scope.
+    parent: Option<DI<DIContainer>>,
+    /// In-flight async creations.
+    flights: Flights,
+    /// Registrations, which take precedence over providers.
+    pub(crate) registry: Registry,
+}
+
+impl Default for DIContainer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DIContainer {
@@ -23,10 +231,76 @@ impl DIContainer {
     pub fn new() -> DIContainer {
         DIContainer {
             #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
-            components: RefCell::new(HashMap::new()),
+            components: RefCell::default(),
             #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
-            components: RwLock::new(HashMap::new()),
+            components: ArcSwap::default(),
+            #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+            write_lock: Mutex::new(()),
+            parent: None,
+            flights: Flights::default(),
+            registry: Registry::default(),
+        }
+    }
+
+    /// Create a builder of a container with pre-registered components.
+    pub fn builder() -> DIContainerBuilder {
+        DIContainerBuilder::default()
+    }
+
+    /// Create a scope, which is a child container of this container.
+    ///
+    /// Components declared as scoped are created per scope,
+    /// and the others are shared with the root container.
+    pub fn scope(self: &DI<Self>) -> DIContainer {
+        DIContainer {
+            parent: Some(self.clone()),
+            ..DIContainer::new()
+        }
+    }
+
+    /// Get the parent container if this is a scope.
+    pub fn parent(&self) -> Option<&DI<DIContainer>> {
+        self.parent.as_ref()
+    }
+
+    /// Get the root container, which holds singleton components.
+    pub fn root(&self) -> &DIContainer {
+        match &self.parent {
+            Some(parent) => parent.root(),
+            None => self,
+        }
+    }
+
+    /// Get the global container, which is used by `di` methods.
+    ///
+    /// If the global container is replaced on the current thread (see `replace_global`), it is returned.
+    pub fn global() -> DI<DIContainer> {
+        if let Some(replaced) = replaced_global() {
+            return replaced;
+        }
+        #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+        return INSTANCE.clone();
+        #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+        return INSTANCE.with(|i| i.clone());
+    }
+
+    /// Get the current container (see `with_container`), or the global container.
+    pub fn current() -> DI<DIContainer> {
+        current_container().unwrap_or_else(Self::global)
+    }
+
+    /// Drop all components held by this container.
+    ///
+    /// This is intended for scopes, e.g. at the end of a request.
+    pub fn dispose(&self) {
+        #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+        self.components.borrow_mut().clear();
+        #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+        {
+            let _lock = unwind::lock(&self.write_lock);
+            self.components.store(Default::default());
         }
+        singleton::invalidate(self);
     }
 
     /// Get a component by type.
@@ -34,59 +308,431 @@ impl DIContainer {
         #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
         let comps = self.components.borrow();
         #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
-        let comps = self.components.read().unwrap();
-        comps
-            .get(std::any::type_name::<T>())
-            .map(|c| c.clone().downcast::<T>().unwrap())
+        let comps = self.components.load();
+        comps.get(&TypeId::of::<T>()).map(Component::downcast)
     }
 
     /// Put a component into the container.
     pub fn put_if_absent<T: DITarget>(&self, c: &DI<T>) -> DI<T> {
+        self.put_unless_valid(c, |_| true)
+    }
+
+    /// Put a component into the container, replacing the previous one.
+    pub(crate) fn put<T: DITarget>(&self, c: &DI<T>) {
+        self.put_unless_valid(c, |_| false);
+    }
+
+    /// Put a component into the container, unless it has a valid one.
+    fn put_unless_valid<T: DITarget>(&self, c: &DI<T>, is_valid: impl Fn(&T) -> bool) -> DI<T> {
+        #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+        let _lock = unwind::lock(&self.write_lock);
+        // a valid component (e.g. put by another thread meanwhile) is returned without copying components.
+        match self.get::<T>() {
+            Some(c) if is_valid(&c) => return c,
+            _ => {}
+        }
         #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
-        let mut components = self.components.borrow_mut();
+        self.components
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), Component::new(c.clone()));
         #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
-        let mut components = self.components.write().unwrap();
-        let key = std::any::type_name::<T>();
-        let value = components
-            .get(key)
-            .map(|c| c.clone().downcast::<T>().unwrap());
-        if let Some(c) = value {
-            c
-        } else {
-            components.insert(key.into(), c.clone());
-            c.clone()
+        if let Some(snapshot) = self
+            .components
+            .load()
+            .insert(TypeId::of::<T>(), Component::new(c.clone()))
+        {
+            self.components.store(DI::new(snapshot));
         }
+        c.clone()
     }
 
     /// Get a component by type with a initialization.
     /// If a target component does not exists, create and put into the container.
+    ///
+    /// A registered component of the type takes precedence. (see [`DIContainer::builder`])
     pub fn get_or_init<T, F>(&self, init: F) -> DI<T>
     where
         T: DITarget,
         F: Fn() -> T,
     {
-        if let Some(c) = self.get::<T>() {
-            c
-        } else {
-            let c = DI::new(init());
-            self.put_if_absent(&c)
+        if let Some(c) = self.resolve_registered::<T>() {
+            return c;
         }
+        self.get_or_create(type_name::<T>(), |_| true, init)
     }
 
     /// Get a component by type with a async initialization.
     /// If a target component does not exists, create and put into the container.
+    ///
+    /// While a component is being created, concurrent calls for the same type wait for it
+    /// instead of creating another instance.
+    ///
+    /// A registered component of the type takes precedence. (see [`DIContainer::builder`])
     pub async fn get_or_init_async<T, F, Fut>(&self, init: F) -> DI<T>
     where
         T: DITarget,
         F: Fn() -> Fut,
         Fut: Future<Output = T>,
     {
-        if let Some(c) = self.get::<T>() {
-            c
-        } else {
-            let v = init().await;
-            let c = DI::new(v);
-            self.put_if_absent(&c)
+        if let Some(c) = self.resolve_registered_async::<T>().await {
+            return c;
         }
+        self.get_or_create_async(type_name::<T>(), |_| true, init)
+            .await
+    }
+
+    /// Register a factory of a target type, replacing the previous registration.
+    ///
+    /// The registration takes precedence over generated providers resolving on this container or its scopes,
+    /// so that an implementation can be chosen at runtime.
+    /// A component created by the previous registration is created again.
+    ///
+    /// ```ignore
+    /// container.register::<dyn FooI>(|c| match backend {
+    ///     Backend::S3 => S3Storage::di_on(c) as DI<dyn FooI>,
+    ///     Backend::Local => LocalStorage::di_on(c),
+    /// });
+    /// FooIProvider::di_on(&container); // resolved by the factory.
+    /// ```
+    pub fn register<T: ?Sized + DITarget>(&self, f: impl Fn(&DIContainer) -> DI<T> + DITarget) {
+        self.registry.insert(Registration::<T>::Sync(Box::new(f)));
+        singleton::invalidate(self);
+    }
+
+    /// Register an async factory of a target type, replacing the previous registration.
+    ///
+    /// A component registered with an async factory must be resolved asynchronously at first.
+    pub fn register_async<T: ?Sized + DITarget>(
+        &self,
+        f: impl for<'a> Fn(&'a DIContainer) -> BoxFuture<'a, DI<T>> + DITarget,
+    ) {
+        self.registry.insert(Registration::<T>::Async(Box::new(f)));
+        singleton::invalidate(self);
+    }
+
+    /// Resolve a component registered in this container or its ancestors, if any.
+    ///
+    /// Generated providers call this before their own resolution.
+    pub fn resolve_registered<T: ?Sized + DITarget>(&self) -> Option<DI<T>> {
+        let (container, registration) = self.registration::<T>()?;
+        let is_valid = |r: &Registered<T>| r.is_from(&registration);
+        let c = match &*registration {
+            Registration::Sync(f) => container.get_or_create(type_name::<T>(), is_valid, || {
+                Registered::new(f(container), &registration)
+            }),
+            Registration::Async(_) => container
+                .get::<Registered<T>>()
+                .filter(|r| is_valid(r))
+                .unwrap_or_else(|| {
+                    panic!(
+                        "`{}` is registered with an async factory, so it must be resolved asynchronously",
+                        type_name::<T>()
+                    )
+                }),
+        };
+        Some(c.component.clone())
+    }
+
+    /// Resolve a component registered in this container or its ancestors asynchronously, if any.
+    pub async fn resolve_registered_async<T: ?Sized + DITarget>(&self) -> Option<DI<T>> {
+        let (container, registration) = self.registration::<T>()?;
+        let is_valid = |r: &Registered<T>| r.is_from(&registration);
+        let c = match &*registration {
+            Registration::Sync(f) => container.get_or_create(type_name::<T>(), is_valid, || {
+                Registered::new(f(container), &registration)
+            }),
+            Registration::Async(f) => {
+                container
+                    .get_or_create_async(type_name::<T>(), is_valid, || async {
+                        Registered::new(f(container).await, &registration)
+                    })
+                    .await
+            }
+        };
+        Some(c.component.clone())
+    }
+
+    /// Resolve a component of a target type by the name of its binding.
+    ///
+    /// Names are given by `#[provide(FooI, name = "storage.s3")]`, e.g. to choose a component from a config file.
+    /// A name always chooses its implementation, even if the target type is registered on the container.
+    /// `None` if the name isn't bound to the target type.
+    ///
+    /// Panics if the component is created asynchronously, which must be resolved by [`DIContainer::resolve_named_async`].
+    /// Named bindings are indexed on first use, which panics if any name is bound to a target type more than once.
+    ///
+    /// ```ignore
+    /// let storage: Option<DI<dyn FooI>> = container.resolve_named::<dyn FooI>("storage.s3");
+    /// ```
+    pub fn resolve_named<T: ?Sized + DITarget>(&self, name: &str) -> Option<DI<T>> {
+        Some(NamedBinding::find::<T>(name)?.resolve(self))
+    }
+
+    /// Resolve a component of a target type by the name of its binding asynchronously.
+    pub async fn resolve_named_async<T: ?Sized + DITarget>(&self, name: &str) -> Option<DI<T>> {
+        Some(NamedBinding::find::<T>(name)?.resolve_async(self).await)
+    }
+
+    /// Dependency chain of the last component whose creation panicked on the current thread, outermost first.
+    ///
+    /// A panic in creation is raised again with its original payload, and the chain is kept here.
+    ///
+    /// ```ignore
+    /// let payload = std::panic::catch_unwind(|| Hoge::di()).unwrap_err();
+    /// DIContainer::last_panic_chain(); // => Some(["Hoge", "Foo", "Bar"]) if `Bar` panicked.
+    /// ```
+    pub fn last_panic_chain() -> Option<Vec<&'static str>> {
+        unwind::last_chain()
+    }
+
+    /// List all named bindings, sorted by names.
+    pub fn names() -> Vec<&'static NamedBinding> {
+        NamedBinding::all()
+    }
+
+    /// Find a registration and the container that holds it.
+    fn registration<T: ?Sized + 'static>(&self) -> Option<(&DIContainer, DI<Registration<T>>)> {
+        match self.registry.get::<T>() {
+            Some(registration) => Some((self, registration)),
+            None => self.parent.as_ref()?.registration(),
+        }
+    }
+
+    /// Get a valid component, or create and put it.
+    fn get_or_create<T, F>(
+        &self,
+        type_name: &'static str,
+        is_valid: impl Fn(&T) -> bool,
+        init: F,
+    ) -> DI<T>
+    where
+        T: DITarget,
+        F: FnOnce() -> T,
+    {
+        match self.get::<T>() {
+            Some(c) if is_valid(&c) => c,
+            _ => {
+                let c = DI::new(create(type_name, init));
+                self.put_unless_valid(&c, is_valid)
+            }
+        }
+    }
+
+    /// Get a valid component, or create and put it asynchronously.
+    async fn get_or_create_async<T, F, Fut>(
+        &self,
+        type_name: &'static str,
+        is_valid: impl Fn(&T) -> bool,
+        init: F,
+    ) -> DI<T>
+    where
+        T: DITarget,
+        F: Fn() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        loop {
+            match self.get::<T>() {
+                Some(c) if is_valid(&c) => return c,
+                _ => {}
+            }
+            match self.flights.join(TypeId::of::<T>()) {
+                Role::Leader(_guard) => {
+                    let v = create_async(type_name, init()).await;
+                    let c = DI::new(v);
+                    return self.put_unless_valid(&c, is_valid);
+                }
+                // the leader may be cancelled, so check again after landing.
+                Role::Follower(landing) => landing.await,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Foo<const N: usize>;
+
+    #[test]
+    fn test_concurrent_puts() {
+        let c = DI::new(DIContainer::new());
+        let foos = std::thread::scope(|s| {
+            let handles = (0..8)
+                .map(|_| {
+                    s.spawn(|| {
+                        (
+                            c.get_or_init(|| Foo::<0>),
+                            c.get_or_init(|| Foo::<1>),
+                            c.get_or_init(|| Foo::<2>),
+                            c.get_or_init(|| Foo::<3>),
+                        )
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        // no component is lost by concurrent writes.
+        for (f0, f1, f2, f3) in foos {
+            assert!(DI::ptr_eq(&f0, &c.get::<Foo<0>>().unwrap()));
+            assert!(DI::ptr_eq(&f1, &c.get::<Foo<1>>().unwrap()));
+            assert!(DI::ptr_eq(&f2, &c.get::<Foo<2>>().unwrap()));
+            assert!(DI::ptr_eq(&f3, &c.get::<Foo<3>>().unwrap()));
+        }
+
+        c.dispose();
+        assert!(c.get::<Foo<0>>().is_none());
+    }
+
+    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    #[test]
+    fn test_put_existing() {
+        let c = DIContainer::new();
+        let foo = c.get_or_init(|| Foo::<0>);
+        let components = c.components.load_full();
+        // components are not copied for a valid component.
+        assert!(DI::ptr_eq(&c.put_if_absent(&DI::new(Foo::<0>)), &foo));
+        assert!(DI::ptr_eq(&components, &c.components.load_full()));
+    }
+
+    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    #[test]
+    fn test_put_recent() {
+        let c = DIContainer::new();
+        c.get_or_init(|| Foo::<0>);
+        let components = c.components.load_full();
+        // a new component is added to recent components without copying components.
+        let foo1 = c.get_or_init(|| Foo::<1>);
+        let foo2 = c.get_or_init(|| Foo::<2>);
+        assert!(DI::ptr_eq(&components, &c.components.load_full()));
+        assert!(DI::ptr_eq(&foo1, &c.get::<Foo<1>>().unwrap()));
+        assert!(DI::ptr_eq(&foo2, &c.get::<Foo<2>>().unwrap()));
+
+        // a replaced component copies components.
+        let replaced = DI::new(Foo::<1>);
+        c.put(&replaced);
+        assert!(!DI::ptr_eq(&components, &c.components.load_full()));
+        assert!(DI::ptr_eq(&replaced, &c.get::<Foo<1>>().unwrap()));
+        assert!(DI::ptr_eq(&foo2, &c.get::<Foo<2>>().unwrap()));
+    }
+
+    #[test]
+    fn test_put_many() {
+        let c = DIContainer::new();
+        macro_rules! put_all {
+            ($($n:literal)*) => {
+                $(c.get_or_init(|| Foo::<$n>);)*
+                // components are kept across merges of recent components.
+                $(assert!(c.get::<Foo<$n>>().is_some());)*
+            };
+        }
+        put_all!(0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33 34 35 36 37 38 39);
+    }
+
+    #[test]
+    fn test_panic_in_creation() {
+        let c = DIContainer::new();
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            c.get_or_init(|| {
+                c.get_or_init::<Foo<1>, _>(|| panic!("failed"));
+                Foo::<0>
+            })
+        }))
+        .unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>().unwrap(), &"failed");
+        assert_eq!(
+            DIContainer::last_panic_chain().unwrap(),
+            vec![
+                std::any::type_name::<Foo<0>>(),
+                std::any::type_name::<Foo<1>>()
+            ]
+        );
+        assert!(c.get::<Foo<0>>().is_none());
+        assert!(c.get::<Foo<1>>().is_none());
+
+        // created again on the next resolution.
+        c.get_or_init(|| Foo::<0>);
+        assert!(c.get::<Foo<0>>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_panic_in_async_creation() {
+        static FAILS: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
+        async fn resolve(c: DI<DIContainer>) -> DI<Foo<0>> {
+            c.get_or_init_async(|| async {
+                tokio::task::yield_now().await;
+                if FAILS.swap(false, std::sync::atomic::Ordering::SeqCst) {
+                    panic!("failed");
+                }
+                Foo::<0>
+            })
+            .await
+        }
+
+        let c = DI::new(DIContainer::new());
+        let leader = tokio::spawn(resolve(c.clone()));
+        let follower = tokio::spawn(resolve(c.clone()));
+        assert!(leader.await.unwrap_err().is_panic());
+        // the follower takes over the creation.
+        let foo = follower.await.unwrap();
+        assert!(DI::ptr_eq(&foo, &c.get::<Foo<0>>().unwrap()));
+    }
+
+    #[test]
+    fn test_register() {
+        let c = DIContainer::new();
+        c.register::<Foo<0>>(|_| DI::new(Foo::<0>));
+        let foo = c.get_or_init(|| unreachable!());
+        assert!(DI::ptr_eq(&foo, &c.resolve_registered::<Foo<0>>().unwrap()));
+
+        // a replaced registration creates the component again.
+        c.register::<Foo<0>>(|_| DI::new(Foo::<0>));
+        let foo2 = c.get_or_init(|| unreachable!());
+        assert!(!DI::ptr_eq(&foo, &foo2));
+        assert!(DI::ptr_eq(&foo2, &c.resolve_registered::<Foo<0>>().unwrap()));
+        assert!(c.resolve_registered::<Foo<1>>().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_register_async() {
+        let c = DI::new(DIContainer::new());
+        c.register_async::<Foo<0>>(|_| Box::pin(async { DI::new(Foo::<0>) }));
+        let scope = c.scope();
+        let foo = scope.get_or_init_async(|| async { unreachable!() }).await;
+        assert!(DI::ptr_eq(&foo, &c.resolve_registered::<Foo<0>>().unwrap()));
+        assert!(DI::ptr_eq(
+            &foo,
+            &scope.resolve_registered_async::<Foo<0>>().await.unwrap()
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "must be resolved asynchronously")]
+    fn test_register_async_resolved_sync() {
+        let c = DIContainer::new();
+        c.register_async::<Foo<0>>(|_| Box::pin(async { DI::new(Foo::<0>) }));
+        c.resolve_registered::<Foo<0>>();
+    }
+
+    #[test]
+    fn test_types_with_same_name() {
+        fn put<T: DITarget>(c: &DIContainer, v: T) -> DI<T> {
+            c.put_if_absent(&DI::new(v))
+        }
+        // closures in a function have the same type name.
+        let (f1, f2) = (|| 1, || 2);
+        assert_eq!(
+            std::any::type_name_of_val(&f1),
+            std::any::type_name_of_val(&f2)
+        );
+
+        let c = DIContainer::new();
+        let f1 = put(&c, f1);
+        let f2 = put(&c, f2);
+        assert_eq!((f1(), f2()), (1, 2));
     }
 }
@@ -0,0 +1,297 @@
+//! Current container, which `di` methods resolve on.
+//!
+//! With the `tokio` feature, the current container is a tokio task-local value.
+//! Otherwise it is a thread-local value that is set while the wrapped future is polled.
+//! In both cases, spawned tasks don't inherit the current container.
+//!
+//! The global container, which is used without a current container, can be replaced on a thread
+//! by [`replace_global`], or for a future by [`replace_global_in`], e.g. to isolate tests from each other.
+
+use std::{future::Future, marker::PhantomData, sync::atomic::Ordering};
+
+use crate::{
+    container::DIContainer,
+    globals::{OVERRIDES, REPLACED},
+    types::DI,
+};
+
+/// Run a future with a current container.
+///
+/// `di` methods called in the future resolve on the container instead of the global container.
+/// ```ignore
+/// let c = DI::new(DIContainer::new());
+/// with_container(&c, async {
+///     Hoge::di(); // resolved on `c`
+/// }).await;
+/// ```
+pub async fn with_container<F: Future>(container: &DI<DIContainer>, fut: F) -> F::Output {
+    let _overriding = Overriding::new();
+    imp::scope(container.clone(), fut).await
+}
+
+/// Run a closure with a current container.
+pub fn with_container_sync<R>(container: &DI<DIContainer>, f: impl FnOnce() -> R) -> R {
+    let _overriding = Overriding::new();
+    imp::sync_scope(container.clone(), f)
+}
+
+/// Replace the global container on the current thread while the guard is alive.
+///
+/// ```ignore
+/// let _guard = replace_global(DI::new(DIContainer::new()));
+/// Hoge::di(); // resolved on the replaced container
+/// ```
+pub fn replace_global(container: DI<DIContainer>) -> GlobalGuard {
+    let overriding = Overriding::new();
+    let prev = REPLACED.with(|r| r.replace(Some(container)));
+    GlobalGuard {
+        prev,
+        _overriding: overriding,
+        _not_send: PhantomData,
+    }
+}
+
+/// Replace the global container on the current thread with a new container while the guard is alive.
+pub fn isolate_global() -> GlobalGuard {
+    replace_global(DI::new(DIContainer::new()))
+}
+
+/// Replace the global container while a future runs.
+///
+/// Unlike [`replace_global`], the replacement follows the future across threads, e.g. on a multi-thread runtime.
+/// With the `tokio` feature, it is a tokio task-local value, which takes precedence over [`replace_global`].
+/// Otherwise it is set on a thread while the future is polled. In both cases, spawned tasks don't inherit it.
+/// ```ignore
+/// replace_global_in(DI::new(DIContainer::new()), async {
+///     Hoge::di(); // resolved on the replaced container
+/// }).await;
+/// ```
+pub async fn replace_global_in<F: Future>(container: DI<DIContainer>, fut: F) -> F::Output {
+    let _overriding = Overriding::new();
+    imp::replace_global_scope(container, fut).await
+}
+
+/// Replace the global container with a new container while a future runs. (see [`replace_global_in`])
+pub async fn isolate_global_in<F: Future>(fut: F) -> F::Output {
+    replace_global_in(DI::new(DIContainer::new()), fut).await
+}
+
+/// Guard of [`replace_global`], which restores the previous global container on drop.
+#[must_use = "the global container is restored when the guard is dropped"]
+pub struct GlobalGuard {
+    prev: Option<DI<DIContainer>>,
+    _overriding: Overriding,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for GlobalGuard {
+    fn drop(&mut self) {
+        let prev = self.prev.take();
+        REPLACED.with(|r| *r.borrow_mut() = prev);
+    }
+}
+
+/// A current container or a replaced global container, which is counted in `OVERRIDES` while alive.
+///
+/// Only the thread (or task) that sets it needs to see the count, so it is relaxed.
+struct Overriding;
+
+impl Overriding {
+    fn new() -> Self {
+        OVERRIDES.fetch_add(1, Ordering::Relaxed);
+        Overriding
+    }
+}
+
+impl Drop for Overriding {
+    fn drop(&mut self) {
+        OVERRIDES.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Whether a current container or a replaced global container may be set on this thread (or task).
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+pub(crate) fn may_override() -> bool {
+    OVERRIDES.load(Ordering::Relaxed) > 0
+}
+
+/// Get the current container if any.
+pub(crate) fn current_container() -> Option<DI<DIContainer>> {
+    imp::current()
+}
+
+/// Get the replaced global container if any.
+pub(crate) fn replaced_global() -> Option<DI<DIContainer>> {
+    imp::replaced_global().or_else(|| REPLACED.with(|r| r.borrow().clone()))
+}
+
+#[cfg(feature = "tokio")]
+mod imp {
+    use super::*;
+
+    tokio::task_local! {
+        static CURRENT: DI<DIContainer>;
+        static GLOBAL: DI<DIContainer>;
+    }
+
+    pub(super) async fn scope<F: Future>(container: DI<DIContainer>, fut: F) -> F::Output {
+        CURRENT.scope(container, fut).await
+    }
+
+    pub(super) fn sync_scope<R>(container: DI<DIContainer>, f: impl FnOnce() -> R) -> R {
+        CURRENT.sync_scope(container, f)
+    }
+
+    pub(super) fn current() -> Option<DI<DIContainer>> {
+        CURRENT.try_with(|c| c.clone()).ok()
+    }
+
+    pub(super) async fn replace_global_scope<F: Future>(
+        container: DI<DIContainer>,
+        fut: F,
+    ) -> F::Output {
+        GLOBAL.scope(container, fut).await
+    }
+
+    pub(super) fn replaced_global() -> Option<DI<DIContainer>> {
+        GLOBAL.try_with(|c| c.clone()).ok()
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+mod imp {
+    use super::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static CURRENT: RefCell<Option<DI<DIContainer>>> = const { RefCell::new(None) };
+    }
+
+    /// Restore the previous container on drop.
+    struct Guard(Option<DI<DIContainer>>);
+
+    impl Guard {
+        fn set(container: DI<DIContainer>) -> Self {
+            Guard(CURRENT.with(|c| c.replace(Some(container))))
+        }
+    }
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            CURRENT.with(|c| *c.borrow_mut() = prev);
+        }
+    }
+
+    pub(super) async fn scope<F: Future>(container: DI<DIContainer>, fut: F) -> F::Output {
+        let mut fut = Box::pin(fut);
+        std::future::poll_fn(move |cx| {
+            let _guard = Guard::set(container.clone());
+            fut.as_mut().poll(cx)
+        })
+        .await
+    }
+
+    pub(super) fn sync_scope<R>(container: DI<DIContainer>, f: impl FnOnce() -> R) -> R {
+        let _guard = Guard::set(container);
+        f()
+    }
+
+    pub(super) fn current() -> Option<DI<DIContainer>> {
+        CURRENT.with(|c| c.borrow().clone())
+    }
+
+    pub(super) async fn replace_global_scope<F: Future>(
+        container: DI<DIContainer>,
+        fut: F,
+    ) -> F::Output {
+        let mut fut = Box::pin(fut);
+        std::future::poll_fn(move |cx| {
+            let _guard = replace_global(container.clone());
+            fut.as_mut().poll(cx)
+        })
+        .await
+    }
+
+    pub(super) fn replaced_global() -> Option<DI<DIContainer>> {
+        // the replacement of a future is set on the thread while it is polled.
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::DIPortal;
+
+    struct Hoge;
+    impl DIPortal for Hoge {
+        fn create_for_di(_container: &DIContainer) -> Self {
+            Hoge {}
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_container() {
+        let c1 = DI::new(DIContainer::new());
+        let c2 = DI::new(DIContainer::new());
+
+        let hoge1 = with_container(&c1, async {
+            tokio::task::yield_now().await;
+            let hoge = Hoge::di();
+            // nested
+            with_container(&c2, async { Hoge::di() }).await;
+            assert!(DI::ptr_eq(&hoge, &Hoge::di()));
+            hoge
+        })
+        .await;
+
+        assert!(DI::ptr_eq(&hoge1, &c1.get::<Hoge>().unwrap()));
+        assert!(c2.get::<Hoge>().is_some());
+        assert!(current_container().is_none());
+    }
+
+    #[test]
+    fn test_with_container_sync() {
+        let c = DI::new(DIContainer::new());
+        let hoge = with_container_sync(&c, Hoge::di);
+        assert!(DI::ptr_eq(&hoge, &c.get::<Hoge>().unwrap()));
+        assert!(current_container().is_none());
+    }
+
+    #[test]
+    fn test_replace_global() {
+        let global = DIContainer::global();
+        {
+            let _guard = isolate_global();
+            let hoge = Hoge::di();
+            assert!(DI::ptr_eq(
+                &hoge,
+                &DIContainer::global().get::<Hoge>().unwrap()
+            ));
+            assert!(global.get::<Hoge>().is_none());
+
+            // another thread uses the original global container.
+            std::thread::spawn(Hoge::di).join().unwrap();
+            assert!(global.get::<Hoge>().is_some());
+        }
+        assert!(DI::ptr_eq(&global, &DIContainer::global()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_replace_global_in() {
+        let global = DIContainer::global();
+        let replaced = DI::new(DIContainer::new());
+        let inner = replaced.clone();
+        let hoge = tokio::spawn(replace_global_in(replaced.clone(), async move {
+            tokio::task::yield_now().await;
+            assert!(DI::ptr_eq(&DIContainer::global(), &inner));
+            Hoge::di()
+        }))
+        .await
+        .unwrap();
+
+        assert!(DI::ptr_eq(&hoge, &replaced.get::<Hoge>().unwrap()));
+        assert!(DI::ptr_eq(&global, &DIContainer::global()));
+    }
+}
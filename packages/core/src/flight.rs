@@ -0,0 +1,99 @@
+//! Single-flight of async component creation.
+//!
+//! While a component is being created asynchronously, other resolutions of the same component
+//! on the same container wait for it instead of creating another instance.
+
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+use crate::{types::DI, unwind::lock};
+
+/// In-flight creations by type.
+#[derive(Debug, Default)]
+pub(crate) struct Flights {
+    flights: Mutex<HashMap<TypeId, DI<Flight>>>,
+}
+
+/// Role of a resolution for an in-flight creation.
+pub(crate) enum Role<'a> {
+    /// Creates a component. The flight lands when the guard is dropped.
+    Leader(LeaderGuard<'a>),
+    /// Waits for the leader.
+    Follower(Landing),
+}
+
+impl Flights {
+    /// Join the flight of a type, or start a new flight as a leader.
+    pub(crate) fn join(&self, key: TypeId) -> Role<'_> {
+        let mut flights = lock(&self.flights);
+        match flights.get(&key) {
+            Some(flight) => Role::Follower(Landing(flight.clone())),
+            None => {
+                let flight = DI::new(Flight::default());
+                flights.insert(key, flight.clone());
+                Role::Leader(LeaderGuard {
+                    flights: self,
+                    key,
+                    flight,
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Flight {
+    landed: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+/// Lands a flight on drop, even if the creation is cancelled,
+/// so that a follower can take over as a new leader.
+pub(crate) struct LeaderGuard<'a> {
+    flights: &'a Flights,
+    key: TypeId,
+    flight: DI<Flight>,
+}
+
+impl Drop for LeaderGuard<'_> {
+    fn drop(&mut self) {
+        lock(&self.flights.flights).remove(&self.key);
+        self.flight.landed.store(true, Ordering::Release);
+        let wakers = std::mem::take(&mut *lock(&self.flight.wakers));
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// Future that completes when a flight lands.
+pub(crate) struct Landing(DI<Flight>);
+
+impl Future for Landing {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0.landed.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+        {
+            let mut wakers = lock(&self.0.wakers);
+            if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+        }
+        // check again, the flight may land while registering.
+        if self.0.landed.load(Ordering::Acquire) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -1,13 +1,24 @@
 //! Global variables.
 
 use crate::container::DIContainer;
+use crate::types::DI;
 #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
 use once_cell::sync::Lazy;
+use std::sync::atomic::AtomicUsize;
 
 /// Global container instance.
 #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
-pub(crate) static INSTANCE: Lazy<DIContainer> = Lazy::new(|| DIContainer::new());
+pub(crate) static INSTANCE: Lazy<DI<DIContainer>> = Lazy::new(|| DI::new(DIContainer::new()));
 #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
 thread_local! {
     pub(crate) static INSTANCE: std::rc::Rc<DIContainer> = std::rc::Rc::new(DIContainer::new());
 }
+
+/// Number of current containers and replaced global containers set on any thread or task,
+/// so that static singletons look them up only while any is set.
+pub(crate) static OVERRIDES: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Global container replaced on the current thread. (see `replace_global`)
+    pub(crate) static REPLACED: std::cell::RefCell<Option<DI<DIContainer>>> = const { std::cell::RefCell::new(None) };
+}
@@ -0,0 +1,88 @@
+//! Join futures concurrently, used by generated code. (see [`join!`](crate::join!))
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Polls multiple futures concurrently in an async context, and returns a tuple of their outputs.
+///
+/// ```ignore
+/// let (foo, bar) = portaldi::join!(Foo::di(), BarProvider::di());
+/// ```
+#[macro_export]
+macro_rules! join {
+    (@ { ( $($count:tt)* ) $( ( $($skip:tt)* ) $e:expr, )* }) => {{
+        // futures are pinned in place, as `pin!` in a struct expression lives as long as `futures`.
+        let mut futures = ( $( $crate::join::MaybeDone { future: ::std::pin::pin!($e), output: None }, )* );
+        ::std::future::poll_fn(move |cx| {
+            let mut is_done = true;
+            $(
+                let ( $($skip,)* fut, .. ) = &mut futures;
+                is_done &= fut.poll_done(cx);
+            )*
+            if is_done {
+                ::std::task::Poll::Ready(( $({
+                    let ( $($skip,)* fut, .. ) = &mut futures;
+                    fut.take_output()
+                },)* ))
+            } else {
+                ::std::task::Poll::Pending
+            }
+        })
+        .await
+    }};
+    (@ { ( $($count:tt)* ) $($t:tt)* } $e:expr, $($rest:expr,)*) => {
+        $crate::join!(@ { ( $($count)* _ ) $($t)* ( $($count)* ) $e, } $($rest,)*)
+    };
+    ($($e:expr),+ $(,)?) => {
+        $crate::join!(@ { () } $($e,)+)
+    };
+}
+
+/// A pinned future and its output.
+#[doc(hidden)]
+pub struct MaybeDone<'a, F: Future> {
+    pub future: Pin<&'a mut F>,
+    pub output: Option<F::Output>,
+}
+
+impl<F: Future> MaybeDone<'_, F> {
+    /// Poll the future if not done yet, and returns whether it is done.
+    pub fn poll_done(&mut self, cx: &mut Context<'_>) -> bool {
+        if self.output.is_none() {
+            match self.future.as_mut().poll(cx) {
+                Poll::Ready(output) => self.output = Some(output),
+                Poll::Pending => return false,
+            }
+        }
+        true
+    }
+
+    /// Take the output of the done future.
+    pub fn take_output(&mut self) -> F::Output {
+        self.output
+            .take()
+            .expect("output of a future is not available")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn test_join() {
+        let (a, b, c) = crate::join!(
+            async {
+                tokio::task::yield_now().await;
+                1
+            },
+            async { "2" },
+            std::future::ready(3.0),
+        );
+        assert_eq!((a, b, c), (1, "2", 3.0));
+
+        let (a,) = crate::join!(async { 1 });
+        assert_eq!(a, 1);
+    }
+}
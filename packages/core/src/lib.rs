@@ -1,9 +1,23 @@
 //! Defines core functionalities.
 
 // export modules
+pub mod builder;
 pub mod container;
+pub mod current;
+pub mod join;
+pub mod module;
+pub mod named;
+pub mod resolver;
+pub mod singleton;
 pub mod traits;
 pub mod types;
 
+/// Re-export for named bindings, so that generated code doesn't require `inventory` dependency.
+#[doc(hidden)]
+pub use inventory;
+
 // privete modules
+mod flight;
 mod globals;
+mod registry;
+mod unwind;
@@ -0,0 +1,32 @@
+//! Define provider modules, which bundle providers and expose them at runtime.
+
+use std::any::TypeId;
+
+/// A binding from a target type to its provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    /// Target type as written in the module, e.g. `dyn FooI`.
+    pub target: &'static str,
+    /// `TypeId` of the target type, which identifies a binding.
+    pub target_id: TypeId,
+    /// Provider type name.
+    pub provider: &'static str,
+    /// Path of the module that declares this binding.
+    pub module: &'static str,
+}
+
+/// Represent a provider module generated by `portaldi::module!`.
+pub trait DIModule {
+    /// Path of this module.
+    fn name() -> &'static str;
+
+    /// Bindings declared by this module and its included modules.
+    fn bindings() -> Vec<Binding>;
+
+    /// Find a binding for a target type, e.g. `binding_of::<dyn FooI>()`.
+    fn binding_of<T: ?Sized + 'static>() -> Option<Binding> {
+        Self::bindings()
+            .into_iter()
+            .find(|b| b.target_id == TypeId::of::<T>())
+    }
+}
@@ -0,0 +1,215 @@
+//! Named bindings, which resolve components by names given at runtime, e.g. from a config file.
+//!
+//! `#[provide(FooI, name = "storage.s3")]` submits a binding of the generated provider,
+//! and bindings of all crates are collected at program start. (see [`DIContainer::resolve_named`])
+//! They are indexed on first use, which panics if a name is bound to the same target type more than once.
+
+use once_cell::sync::Lazy;
+use std::{
+    any::{type_name, TypeId},
+    collections::HashMap,
+};
+
+use crate::{
+    builder::BoxFuture,
+    container::{AnyDI, DIContainer},
+    resolver::{DIMode, DIResolver, SyncToken},
+    traits::DITarget,
+    types::DI,
+};
+
+/// A binding from a name to a provider of a target type.
+pub struct NamedBinding {
+    name: &'static str,
+    target: fn() -> &'static str,
+    target_id: fn() -> TypeId,
+    is_async: bool,
+    resolve_sync: fn(&DIContainer) -> Option<AnyDI>,
+    resolve_async: for<'a> fn(&'a DIContainer) -> BoxFuture<'a, AnyDI>,
+}
+
+inventory::collect!(NamedBinding);
+
+/// Named bindings of all crates, indexed on first use.
+static BINDINGS: Lazy<NamedBindings> =
+    Lazy::new(|| NamedBindings::new(inventory::iter::<NamedBinding>()));
+
+/// Named bindings by target types and names.
+struct NamedBindings {
+    all: Vec<&'static NamedBinding>,
+    by_target: HashMap<TypeId, HashMap<&'static str, &'static NamedBinding>>,
+}
+
+impl NamedBindings {
+    /// Index bindings, sorted by names and targets.
+    ///
+    /// A name bound to the same target type more than once panics, because it can't choose an implementation.
+    fn new(bindings: impl IntoIterator<Item = &'static NamedBinding>) -> Self {
+        let mut all = bindings.into_iter().collect::<Vec<_>>();
+        all.sort_by_key(|b| (b.name, b.target()));
+        let mut by_target = HashMap::<_, HashMap<_, _>>::new();
+        for binding in &all {
+            let names = by_target.entry((binding.target_id)()).or_default();
+            if names.insert(binding.name, *binding).is_some() {
+                panic!(
+                    "`{}` is bound to the name `{}` more than once",
+                    binding.target(),
+                    binding.name
+                );
+            }
+        }
+        NamedBindings { all, by_target }
+    }
+}
+
+/// Resolution of a named provider, which macros implement for a binding.
+///
+/// Unlike [`DIResolver`], it doesn't look up registrations of a container,
+/// so that a name chooses its implementation even if the target type is registered.
+#[doc(hidden)]
+pub trait NamedResolver: DIResolver {
+    /// Resolve synchronously. This is called only in [`SyncMode`](crate::resolver::SyncMode),
+    /// so an async provider doesn't implement it.
+    fn resolve_named_sync(
+        container: &DIContainer,
+        token: SyncToken<Self::Mode>,
+    ) -> DI<Self::Output> {
+        let _ = container;
+        token.unreachable()
+    }
+
+    /// Resolve asynchronously. This can be called in both modes.
+    fn resolve_named_async(container: &DIContainer) -> BoxFuture<'_, DI<Self::Output>>;
+}
+
+impl NamedBinding {
+    /// A binding of a provider, which is submitted by macros.
+    #[doc(hidden)]
+    pub const fn new<P>(name: &'static str) -> Self
+    where
+        P: NamedResolver,
+        P::Output: DITarget,
+    {
+        NamedBinding {
+            name,
+            target: type_name::<P::Output>,
+            target_id: TypeId::of::<P::Output>,
+            is_async: <P::Mode as DIMode>::IS_ASYNC,
+            resolve_sync: resolve_sync::<P>,
+            resolve_async: resolve_async::<P>,
+        }
+    }
+
+    /// Name of this binding, e.g. `storage.s3`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Target type, e.g. `dyn FooI`.
+    pub fn target(&self) -> &'static str {
+        (self.target)()
+    }
+
+    /// Whether the component is created asynchronously.
+    pub fn is_async(&self) -> bool {
+        self.is_async
+    }
+
+    /// All named bindings, sorted by names and targets.
+    pub(crate) fn all() -> Vec<&'static NamedBinding> {
+        BINDINGS.all.clone()
+    }
+
+    /// Find a binding of a target type by name.
+    pub(crate) fn find<T: ?Sized + 'static>(name: &str) -> Option<&'static NamedBinding> {
+        BINDINGS
+            .by_target
+            .get(&TypeId::of::<T>())?
+            .get(name)
+            .copied()
+    }
+
+    /// Resolve synchronously, which panics if the component is created asynchronously.
+    pub(crate) fn resolve<T: ?Sized + DITarget>(&self, container: &DIContainer) -> DI<T> {
+        match (self.resolve_sync)(container) {
+            Some(c) => downcast(c),
+            None => panic!(
+                "`{}` named `{}` is created asynchronously, so it must be resolved by `resolve_named_async`",
+                self.target(),
+                self.name
+            ),
+        }
+    }
+
+    pub(crate) async fn resolve_async<T: ?Sized + DITarget>(
+        &self,
+        container: &DIContainer,
+    ) -> DI<T> {
+        downcast((self.resolve_async)(container).await)
+    }
+}
+
+impl std::fmt::Debug for NamedBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NamedBinding")
+            .field("name", &self.name)
+            .field("target", &self.target())
+            .field("is_async", &self.is_async)
+            .finish()
+    }
+}
+
+fn resolve_sync<P>(container: &DIContainer) -> Option<AnyDI>
+where
+    P: NamedResolver,
+    P::Output: DITarget,
+{
+    let token = SyncToken::of()?;
+    Some(DI::new(P::resolve_named_sync(container, token)))
+}
+
+fn resolve_async<P>(container: &DIContainer) -> BoxFuture<'_, AnyDI>
+where
+    P: NamedResolver,
+    P::Output: DITarget,
+{
+    Box::pin(async move { DI::new(P::resolve_named_async(container).await) as AnyDI })
+}
+
+fn downcast<T: ?Sized + DITarget>(c: AnyDI) -> DI<T> {
+    // bindings are found by `TypeId`, so the type always matches.
+    DI::clone(&c.downcast::<DI<T>>().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    trait QueueI {}
+
+    fn binding(name: &'static str) -> &'static NamedBinding {
+        Box::leak(Box::new(NamedBinding {
+            name,
+            target: type_name::<dyn QueueI>,
+            target_id: TypeId::of::<dyn QueueI>,
+            is_async: false,
+            resolve_sync: |_| None,
+            resolve_async: |_| unreachable!(),
+        }))
+    }
+
+    #[test]
+    fn test_index() {
+        let bindings = NamedBindings::new([binding("queue.sqs"), binding("queue.memory")]);
+        let names = bindings.all.iter().map(|b| b.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["queue.memory", "queue.sqs"]);
+        let sqs = bindings.by_target[&TypeId::of::<dyn QueueI>()]["queue.sqs"];
+        assert_eq!(sqs.name(), "queue.sqs");
+    }
+
+    #[test]
+    #[should_panic(expected = "is bound to the name `queue.sqs` more than once")]
+    fn test_index_duplicate() {
+        NamedBindings::new([binding("queue.sqs"), binding("queue.sqs")]);
+    }
+}
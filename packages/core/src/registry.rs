@@ -0,0 +1,112 @@
+//! Registrations of components, which take precedence over providers. (see `DIContainerBuilder`)
+//!
+//! Registrations are held like components, in an immutable map that is swapped atomically on writes.
+
+use std::{any::TypeId, collections::HashMap, hash::BuildHasherDefault};
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+use arc_swap::ArcSwap;
+#[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+use std::cell::RefCell;
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+use std::sync::Mutex;
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+use crate::unwind::lock;
+use crate::{
+    builder::BoxFuture,
+    container::{AnyDI, DIContainer, TypeIdHasher},
+    types::DI,
+};
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+pub(crate) type SyncFactory<T> = dyn Fn(&DIContainer) -> DI<T> + Send + Sync;
+#[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+pub(crate) type SyncFactory<T> = dyn Fn(&DIContainer) -> DI<T>;
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+pub(crate) type AsyncFactory<T> =
+    dyn for<'a> Fn(&'a DIContainer) -> BoxFuture<'a, DI<T>> + Send + Sync;
+#[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+pub(crate) type AsyncFactory<T> = dyn for<'a> Fn(&'a DIContainer) -> BoxFuture<'a, DI<T>>;
+
+/// How a registered component is created.
+pub(crate) enum Registration<T: ?Sized> {
+    Sync(Box<SyncFactory<T>>),
+    Async(Box<AsyncFactory<T>>),
+}
+
+/// A created component of a registration, which is held as a component of the registering container.
+pub(crate) struct Registered<T: ?Sized> {
+    pub(crate) component: DI<T>,
+    /// The registration that created the component, which may be replaced later.
+    registration: DI<Registration<T>>,
+}
+
+impl<T: ?Sized> Registered<T> {
+    pub(crate) fn new(component: DI<T>, registration: &DI<Registration<T>>) -> Self {
+        Self {
+            component,
+            registration: registration.clone(),
+        }
+    }
+
+    pub(crate) fn is_from(&self, registration: &DI<Registration<T>>) -> bool {
+        DI::ptr_eq(&self.registration, registration)
+    }
+}
+
+type Registrations = HashMap<TypeId, AnyDI, BuildHasherDefault<TypeIdHasher>>;
+
+/// Registrations by target type.
+#[derive(Default)]
+pub(crate) struct Registry {
+    #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+    registrations: RefCell<Registrations>,
+    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    registrations: ArcSwap<Registrations>,
+    /// Serialize writes of registrations.
+    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    write_lock: Mutex<()>,
+}
+
+impl Registry {
+    /// Get a registration of a target type.
+    pub(crate) fn get<T: ?Sized + 'static>(&self) -> Option<DI<Registration<T>>> {
+        #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+        let registrations = self.registrations.borrow();
+        #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+        let registrations = self.registrations.load();
+        if registrations.is_empty() {
+            return None;
+        }
+        let r = registrations.get(&TypeId::of::<T>())?.clone();
+        // keyed by `TypeId`, so the type always matches.
+        Some(r.downcast::<Registration<T>>().unwrap())
+    }
+
+    /// Register a target type, replacing the previous registration.
+    pub(crate) fn insert<T: ?Sized + 'static>(&self, r: Registration<T>) {
+        #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+        self.registrations
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), DI::new(r));
+        #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+        {
+            let _lock = lock(&self.write_lock);
+            let mut registrations = Registrations::clone(&self.registrations.load());
+            registrations.insert(TypeId::of::<T>(), DI::new(r));
+            self.registrations.store(DI::new(registrations));
+        }
+    }
+}
+
+impl std::fmt::Debug for Registry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+        let len = self.registrations.borrow().len();
+        #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+        let len = self.registrations.load().len();
+        f.debug_struct("Registry").field("len", &len).finish()
+    }
+}
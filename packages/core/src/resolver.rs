@@ -0,0 +1,472 @@
+//! Unified resolution of sync and async components.
+//!
+//! Whether a component is created synchronously or asynchronously is represented by a mode type
+//! ([`SyncMode`] or [`AsyncMode`]), so that a derived component can infer its own mode from its dependencies.
+//! Portals and providers of each mode get [`DIPortal`] / [`AsyncDIPortal`] and
+//! [`DIProvider`] / [`AsyncDIProvider`] implementations by the mode.
+
+#[cfg(not(feature = "native-async"))]
+use async_trait::async_trait;
+#[cfg(feature = "native-async")]
+use std::future::Future;
+use std::{
+    future::{ready, Ready},
+    marker::PhantomData,
+};
+
+#[cfg(feature = "native-async")]
+use crate::traits::MaybeSend;
+use crate::{
+    builder::BoxFuture,
+    container::DIContainer,
+    singleton::StaticSingleton,
+    traits::{AsyncDIPortal, AsyncDIProvider, DIPortal, DIProvider, DITarget},
+    types::DI,
+};
+
+/// Mode of components, which represents whether a component is created asynchronously.
+///
+/// The mode is a const parameter, so that a mode can be computed from modes of dependencies
+/// without naming them in a type. (e.g. `ResolveMode<{ A::IS_ASYNC || B::IS_ASYNC }>`)
+pub struct ResolveMode<const ASYNC: bool>;
+
+/// Mode of components created synchronously.
+pub type SyncMode = ResolveMode<false>;
+/// Mode of components created asynchronously.
+pub type AsyncMode = ResolveMode<true>;
+
+/// Represent a mode of components.
+pub trait DIMode {
+    const IS_ASYNC: bool;
+}
+
+impl<const ASYNC: bool> DIMode for ResolveMode<ASYNC> {
+    const IS_ASYNC: bool = ASYNC;
+}
+
+/// Proof that components of a mode are resolved synchronously, which sync methods of providers and portals take.
+///
+/// A token exists only for [`SyncMode`], so that sync methods of async components can't be called.
+pub struct SyncToken<M>(PhantomData<fn() -> M>);
+
+impl<M> Clone for SyncToken<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for SyncToken<M> {}
+
+impl<M: DIMode> SyncToken<M> {
+    /// A token, if the mode is [`SyncMode`].
+    pub fn of() -> Option<Self> {
+        (!M::IS_ASYNC).then_some(SyncToken(PhantomData))
+    }
+
+    /// A token of another mode, e.g. of a dependency, which is synchronous as a synchronous component is.
+    ///
+    /// # Panics
+    /// Panics if the mode is asynchronous.
+    pub fn cast<N: DIMode>(self) -> SyncToken<N> {
+        SyncToken::of().expect("a dependency of a synchronous component is synchronous")
+    }
+
+    /// Body of sync methods of async components, which are never called without a token.
+    pub(crate) fn unreachable(self) -> ! {
+        unreachable!("a synchronous method of an asynchronous component is called")
+    }
+}
+
+/// Provides component instance for DI types in either mode.
+///
+/// Providers generated by macros implement this trait,
+/// and get [`DIProvider`] or [`AsyncDIProvider`] implementation by the mode.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a `DIResolver`",
+    label = "no provider generated by portaldi",
+    note = "for a hand-written provider, specify it by `#[inject({Self})]` or `#[inject({Self}, async)]`"
+)]
+#[cfg(not(feature = "native-async"))]
+#[cfg_attr(all(target_arch = "wasm32", not(feature = "multi-thread")), async_trait(?Send))]
+#[cfg_attr(
+    any(not(target_arch = "wasm32"), feature = "multi-thread"),
+    async_trait
+)]
+pub trait DIResolver {
+    /// Target type.
+    type Output: ?Sized;
+    /// Whether the component is created asynchronously.
+    type Mode: DIMode;
+
+    /// Resolve synchronously. This is called only in [`SyncMode`], so an async provider doesn't implement it.
+    fn resolve_sync(container: &DIContainer, token: SyncToken<Self::Mode>) -> DI<Self::Output> {
+        let _ = container;
+        token.unreachable()
+    }
+
+    /// Resolve asynchronously. This can be called in both modes.
+    async fn resolve_async(container: &DIContainer) -> DI<Self::Output>;
+}
+
+/// Provides component instance for DI types in either mode.
+///
+/// Providers generated by macros implement this trait,
+/// and get [`DIProvider`] or [`AsyncDIProvider`] implementation by the mode.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a `DIResolver`",
+    label = "no provider generated by portaldi",
+    note = "for a hand-written provider, specify it by `#[inject({Self})]` or `#[inject({Self}, async)]`"
+)]
+#[cfg(feature = "native-async")]
+pub trait DIResolver {
+    /// Target type.
+    type Output: ?Sized;
+    /// Whether the component is created asynchronously.
+    type Mode: DIMode;
+
+    /// Resolve synchronously. This is called only in [`SyncMode`], so an async provider doesn't implement it.
+    fn resolve_sync(container: &DIContainer, token: SyncToken<Self::Mode>) -> DI<Self::Output> {
+        let _ = container;
+        token.unreachable()
+    }
+
+    /// Resolve asynchronously. This can be called in both modes.
+    fn resolve_async(container: &DIContainer) -> impl Future<Output = DI<Self::Output>> + MaybeSend;
+}
+
+/// Default provider of a trait, which is registered on the trait object wherever the provider is generated.
+///
+/// `#[provide(FooI, default)]` or `#[provider(FooI, default)]` implements this for `dyn FooI`,
+/// so that [`ProviderOf`] resolves the provider without naming it,
+/// e.g. `pub type FooIProvider = ProviderOf<dyn FooI>;` next to the trait makes `DI<dyn crate::service::FooI>` work.
+/// A trait can have only one default provider, which is in the crate of the trait.
+///
+/// The implementation forwards to the provider without naming it, so that a private provider doesn't leak.
+#[diagnostic::on_unimplemented(
+    message = "no default provider of `{Self}` is generated",
+    note = "add `default` to `#[provide(..)]` or `#[provider(..)]` of an implementation, e.g. `#[provide(FooI, default)]`"
+)]
+pub trait DIProvided {
+    /// Mode of the provider.
+    type Mode: DIMode;
+
+    /// Resolve synchronously. This is called only in [`SyncMode`], so an async provider doesn't implement it.
+    fn resolve_sync(container: &DIContainer, token: SyncToken<Self::Mode>) -> DI<Self> {
+        let _ = container;
+        token.unreachable()
+    }
+
+    /// Resolve asynchronously. This can be called in both modes.
+    fn resolve_async(container: &DIContainer) -> BoxFuture<'_, DI<Self>>;
+}
+
+/// Default provider of a trait registered by [`DIProvided`], e.g. `ProviderOf::<dyn FooI>::di()`.
+pub struct ProviderOf<T: ?Sized>(PhantomData<fn() -> Box<T>>);
+
+#[cfg_attr(
+    all(
+        not(feature = "native-async"),
+        target_arch = "wasm32",
+        not(feature = "multi-thread")
+    ),
+    async_trait(?Send)
+)]
+#[cfg_attr(
+    all(
+        not(feature = "native-async"),
+        any(not(target_arch = "wasm32"), feature = "multi-thread")
+    ),
+    async_trait
+)]
+impl<T: ?Sized + DIProvided> DIResolver for ProviderOf<T> {
+    type Output = T;
+    type Mode = T::Mode;
+
+    fn resolve_sync(container: &DIContainer, token: SyncToken<T::Mode>) -> DI<T> {
+        T::resolve_sync(container, token)
+    }
+
+    async fn resolve_async(container: &DIContainer) -> DI<T> {
+        T::resolve_async(container).await
+    }
+}
+
+/// Add `di` methods for DI target types in either mode.
+///
+/// `#[derive(DIPortal)]` implements this trait with the mode inferred from dependencies,
+/// and the type gets [`DIPortal`] or [`AsyncDIPortal`] implementation by the mode.
+#[cfg(not(feature = "native-async"))]
+#[cfg_attr(all(target_arch = "wasm32", not(feature = "multi-thread")), async_trait(?Send))]
+#[cfg_attr(
+    any(not(target_arch = "wasm32"), feature = "multi-thread"),
+    async_trait
+)]
+pub trait AutoDIPortal: Sized + DITarget {
+    /// Whether the component is created asynchronously.
+    type Mode: DIMode;
+
+    /// Whether a component is created per scope.
+    /// Otherwise a component is a singleton in the root container.
+    const SCOPED: bool = false;
+
+    /// Create new instance for DI synchronously. This is called only in [`SyncMode`], so an async component doesn't implement it.
+    fn create_sync(container: &DIContainer, token: SyncToken<Self::Mode>) -> Self {
+        let _ = container;
+        token.unreachable()
+    }
+
+    /// Static cache of a singleton for `di()`. (generated by the `static-singletons` feature)
+    #[doc(hidden)]
+    fn static_singleton() -> Option<&'static StaticSingleton<Self>> {
+        None
+    }
+
+    /// Create new instance for DI asynchronously. This is called only in [`AsyncMode`].
+    async fn create_async(container: &DIContainer) -> Self;
+
+    /// Resolve synchronously. This is called only in [`SyncMode`].
+    fn resolve_sync(container: &DIContainer, token: SyncToken<Self::Mode>) -> DI<Self> {
+        let container = if Self::SCOPED {
+            container
+        } else {
+            container.root()
+        };
+        container.get_or_init(|| Self::create_sync(container, token))
+    }
+
+    /// Resolve asynchronously. This can be called in both modes.
+    async fn resolve_async(container: &DIContainer) -> DI<Self> {
+        if let Some(token) = SyncToken::of() {
+            return Self::resolve_sync(container, token);
+        }
+        let container = if Self::SCOPED {
+            container
+        } else {
+            container.root()
+        };
+        container
+            .get_or_init_async(|| Self::create_async(container))
+            .await
+    }
+}
+
+/// Add `di` methods for DI target types in either mode.
+///
+/// `#[derive(DIPortal)]` implements this trait with the mode inferred from dependencies,
+/// and the type gets [`DIPortal`] or [`AsyncDIPortal`] implementation by the mode.
+#[cfg(feature = "native-async")]
+pub trait AutoDIPortal: Sized + DITarget {
+    /// Whether the component is created asynchronously.
+    type Mode: DIMode;
+
+    /// Whether a component is created per scope.
+    /// Otherwise a component is a singleton in the root container.
+    const SCOPED: bool = false;
+
+    /// Create new instance for DI synchronously. This is called only in [`SyncMode`], so an async component doesn't implement it.
+    fn create_sync(container: &DIContainer, token: SyncToken<Self::Mode>) -> Self {
+        let _ = container;
+        token.unreachable()
+    }
+
+    /// Static cache of a singleton for `di()`. (generated by the `static-singletons` feature)
+    #[doc(hidden)]
+    fn static_singleton() -> Option<&'static StaticSingleton<Self>> {
+        None
+    }
+
+    /// Create new instance for DI asynchronously. This is called only in [`AsyncMode`].
+    fn create_async(container: &DIContainer) -> impl Future<Output = Self> + MaybeSend;
+
+    /// Resolve synchronously. This is called only in [`SyncMode`].
+    fn resolve_sync(container: &DIContainer, token: SyncToken<Self::Mode>) -> DI<Self> {
+        let container = if Self::SCOPED {
+            container
+        } else {
+            container.root()
+        };
+        container.get_or_init(|| Self::create_sync(container, token))
+    }
+
+    /// Resolve asynchronously. This can be called in both modes.
+    fn resolve_async(container: &DIContainer) -> impl Future<Output = DI<Self>> + MaybeSend {
+        async move {
+            if let Some(token) = SyncToken::of() {
+                return Self::resolve_sync(container, token);
+            }
+            let container = if Self::SCOPED {
+                container
+            } else {
+                container.root()
+            };
+            container
+                .get_or_init_async(|| Self::create_async(container))
+                .await
+        }
+    }
+}
+
+impl<T: AutoDIPortal<Mode = SyncMode>> DIPortal for T {
+    const SCOPED: bool = <T as AutoDIPortal>::SCOPED;
+
+    fn create_for_di(container: &DIContainer) -> Self {
+        T::create_sync(container, SyncToken(PhantomData))
+    }
+
+    fn static_singleton() -> Option<&'static StaticSingleton<Self>> {
+        <T as AutoDIPortal>::static_singleton()
+    }
+}
+
+#[cfg_attr(
+    all(
+        not(feature = "native-async"),
+        target_arch = "wasm32",
+        not(feature = "multi-thread")
+    ),
+    async_trait(?Send)
+)]
+#[cfg_attr(
+    all(
+        not(feature = "native-async"),
+        any(not(target_arch = "wasm32"), feature = "multi-thread")
+    ),
+    async_trait
+)]
+impl<T: AutoDIPortal<Mode = AsyncMode>> AsyncDIPortal for T {
+    const SCOPED: bool = <T as AutoDIPortal>::SCOPED;
+
+    async fn create_for_di(container: &DIContainer) -> Self {
+        T::create_async(container).await
+    }
+
+    fn static_singleton() -> Option<&'static StaticSingleton<Self>> {
+        <T as AutoDIPortal>::static_singleton()
+    }
+}
+
+impl<P: DIResolver<Mode = SyncMode>> DIProvider for P {
+    type Output = P::Output;
+
+    fn di_on(container: &DIContainer) -> DI<Self::Output> {
+        P::resolve_sync(container, SyncToken(PhantomData))
+    }
+}
+
+#[cfg_attr(
+    all(
+        not(feature = "native-async"),
+        target_arch = "wasm32",
+        not(feature = "multi-thread")
+    ),
+    async_trait(?Send)
+)]
+#[cfg_attr(
+    all(
+        not(feature = "native-async"),
+        any(not(target_arch = "wasm32"), feature = "multi-thread")
+    ),
+    async_trait
+)]
+impl<P: DIResolver<Mode = AsyncMode>> AsyncDIProvider for P {
+    type Output = P::Output;
+
+    async fn di_on(container: &DIContainer) -> DI<Self::Output> {
+        P::resolve_async(container).await
+    }
+}
+
+/// Resolution through a provider, which macros use to inject a dependency without `#[inject]`.
+///
+/// A provider generated by macros resolves through [`DIResolver`], whose mode is inferred.
+/// Otherwise a hand-written provider resolves through [`DIProvider`] or [`AsyncDIProvider`]
+/// by [`ResolveByDIProvider`] or [`ResolveByAsyncDIProvider`], which must be in scope.
+/// (e.g. `ProviderResolution::<FooProvider>::IS_ASYNC`)
+#[doc(hidden)]
+pub struct ProviderResolution<P>(PhantomData<P>);
+
+impl<P> ProviderResolution<P>
+where
+    P: DIResolver + 'static,
+    P::Output: 'static,
+{
+    pub const IS_ASYNC: bool = <P::Mode as DIMode>::IS_ASYNC;
+
+    pub fn resolve_sync(container: &DIContainer, token: SyncToken<P::Mode>) -> DI<P::Output> {
+        P::resolve_sync(container, token)
+    }
+
+    #[cfg(not(feature = "native-async"))]
+    pub fn resolve_async(container: &DIContainer) -> BoxFuture<'_, DI<P::Output>> {
+        P::resolve_async(container)
+    }
+
+    #[cfg(feature = "native-async")]
+    pub fn resolve_async(
+        container: &DIContainer,
+    ) -> impl Future<Output = DI<P::Output>> + MaybeSend + '_ {
+        P::resolve_async(container)
+    }
+}
+
+/// Resolution through a hand-written [`DIProvider`]. (see [`ProviderResolution`])
+#[doc(hidden)]
+pub trait ResolveByDIProvider {
+    type Output: ?Sized;
+
+    const IS_ASYNC: bool = false;
+
+    fn resolve_sync(container: &DIContainer, token: SyncToken<SyncMode>) -> DI<Self::Output>;
+
+    fn resolve_async(container: &DIContainer) -> Ready<DI<Self::Output>>;
+}
+
+impl<P> ResolveByDIProvider for ProviderResolution<P>
+where
+    P: DIProvider,
+    P::Output: DITarget,
+{
+    type Output = P::Output;
+
+    fn resolve_sync(container: &DIContainer, _: SyncToken<SyncMode>) -> DI<P::Output> {
+        P::di_on(container)
+    }
+
+    fn resolve_async(container: &DIContainer) -> Ready<DI<P::Output>> {
+        ready(P::di_on(container))
+    }
+}
+
+/// Resolution through a hand-written [`AsyncDIProvider`]. (see [`ProviderResolution`])
+#[doc(hidden)]
+pub trait ResolveByAsyncDIProvider {
+    type Output: ?Sized;
+
+    const IS_ASYNC: bool = true;
+
+    fn resolve_sync(container: &DIContainer, token: SyncToken<AsyncMode>) -> DI<Self::Output> {
+        let _ = container;
+        token.unreachable()
+    }
+
+    #[cfg(not(feature = "native-async"))]
+    fn resolve_async(container: &DIContainer) -> BoxFuture<'_, DI<Self::Output>>;
+
+    #[cfg(feature = "native-async")]
+    fn resolve_async(container: &DIContainer)
+        -> impl Future<Output = DI<Self::Output>> + MaybeSend;
+}
+
+impl<P: AsyncDIProvider + 'static> ResolveByAsyncDIProvider for ProviderResolution<P> {
+    type Output = P::Output;
+
+    #[cfg(not(feature = "native-async"))]
+    fn resolve_async(container: &DIContainer) -> BoxFuture<'_, DI<P::Output>> {
+        P::di_on(container)
+    }
+
+    #[cfg(feature = "native-async")]
+    fn resolve_async(container: &DIContainer) -> impl Future<Output = DI<P::Output>> + MaybeSend {
+        P::di_on(container)
+    }
+}
@@ -0,0 +1,170 @@
+//! Per-type static singletons for the global container. (`static-singletons` feature)
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    OnceLock,
+};
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+use arc_swap::ArcSwapOption;
+
+use crate::container::DIContainer;
+use crate::traits::DITarget;
+use crate::types::DI;
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+use crate::{
+    current::{current_container, may_override, replaced_global},
+    globals::INSTANCE,
+};
+
+/// Generation of registrations on the global container, which invalidates cached instances.
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Invalidate cached instances if a registration is changed on the global container, or it is disposed.
+pub(crate) fn invalidate(container: &DIContainer) {
+    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    if std::ptr::eq(container, &**INSTANCE) {
+        GENERATION.fetch_add(1, Ordering::AcqRel);
+    }
+    let _ = container;
+}
+
+/// A static cache of a singleton in the global container.
+///
+/// Macros generate a static of this per type, and `di()` reads it without looking up the container
+/// while neither a current container (see `with_container`) nor a replaced global container (see `replace_global`) is set.
+/// The cached instance is the one `di()` resolves on the global container,
+/// which is the registered one if the type is registered by `DIContainer::register`.
+/// A registration on the global container and `dispose` of it invalidate cached instances, so they are resolved again.
+///
+/// The first cached instance is held in a `OnceLock`, so that reading it costs about as much as a plain static
+/// while no current container or replaced global container is set on any thread.
+/// Instances cached after it is invalidated (e.g. in tests) are held in an atomically swapped cell.
+///
+/// Without thread safety (`wasm32` without the `multi-thread` feature), nothing is cached.
+pub struct StaticSingleton<T: DITarget> {
+    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    first: OnceLock<Cached<T>>,
+    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    later: ArcSwapOption<Cached<T>>,
+    #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+    _marker: std::marker::PhantomData<fn() -> DI<T>>,
+}
+
+/// A cached instance with the generation of registrations it is resolved in.
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+struct Cached<T> {
+    generation: u64,
+    c: DI<T>,
+}
+
+impl<T: DITarget> StaticSingleton<T> {
+    pub const fn new() -> Self {
+        StaticSingleton {
+            #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+            first: OnceLock::new(),
+            #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+            later: ArcSwapOption::const_empty(),
+            #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Get the cached instance if `di()` resolves on the global container and registrations are unchanged.
+    pub fn get(&self) -> Option<DI<T>> {
+        #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+        {
+            if may_override() && (current_container().is_some() || replaced_global().is_some()) {
+                return None;
+            }
+            let generation = Self::generation();
+            match self.first.get() {
+                Some(cached) if cached.generation == generation => Some(cached.c.clone()),
+                Some(_) => {
+                    let cached = self.later.load();
+                    let cached = cached.as_ref()?;
+                    (cached.generation == generation).then(|| cached.c.clone())
+                }
+                None => None,
+            }
+        }
+        #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+        None
+    }
+
+    /// Current generation of registrations, which is taken before resolving an instance to `put`.
+    pub fn generation() -> u64 {
+        #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+        return GENERATION.load(Ordering::Acquire);
+        #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+        0
+    }
+
+    /// Cache an instance resolved on a container in a generation if it is the global container, and return it.
+    pub fn put(&self, container: &DIContainer, generation: u64, c: DI<T>) -> DI<T> {
+        #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+        if std::ptr::eq(container, &**INSTANCE) {
+            // an instance resolved before a registration is not cached.
+            if generation == Self::generation() {
+                let cached = Cached {
+                    generation,
+                    c: c.clone(),
+                };
+                if let Err(cached) = self.first.set(cached) {
+                    self.later.store(Some(DI::new(cached)));
+                }
+            }
+            return c;
+        }
+        let _ = (container, generation);
+        c
+    }
+}
+
+impl<T: DITarget> Default for StaticSingleton<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::current::{isolate_global, with_container_sync};
+    use crate::traits::DIPortal;
+
+    struct Hoge;
+    impl DIPortal for Hoge {
+        fn create_for_di(_container: &DIContainer) -> Self {
+            Hoge {}
+        }
+        fn static_singleton() -> Option<&'static StaticSingleton<Self>> {
+            static SINGLETON: StaticSingleton<Hoge> = StaticSingleton::new();
+            Some(&SINGLETON)
+        }
+    }
+
+    #[test]
+    fn test_static_singleton() {
+        let hoge = Hoge::di();
+        assert!(DI::ptr_eq(&hoge, &Hoge::di()));
+        assert!(DI::ptr_eq(&hoge, &Hoge::di_on(&DIContainer::global())));
+        assert!(DI::ptr_eq(
+            &hoge,
+            &Hoge::static_singleton().unwrap().get().unwrap()
+        ));
+
+        // other containers are not affected.
+        let c = DI::new(DIContainer::new());
+        let hoge2 = with_container_sync(&c, Hoge::di);
+        assert!(!DI::ptr_eq(&hoge, &hoge2));
+        assert!(DI::ptr_eq(&hoge2, &c.get::<Hoge>().unwrap()));
+        {
+            let _guard = isolate_global();
+            assert!(!DI::ptr_eq(&hoge, &Hoge::di()));
+        }
+        assert!(DI::ptr_eq(&hoge, &Hoge::di()));
+    }
+}
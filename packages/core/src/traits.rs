@@ -1,9 +1,12 @@
 //! Define traits that expose DI apis to user.
 
+#[cfg(not(feature = "native-async"))]
 use async_trait::async_trait;
+#[cfg(feature = "native-async")]
+use std::future::Future;
 
 use crate::container::DIContainer;
-use crate::globals::INSTANCE;
+use crate::singleton::StaticSingleton;
 use crate::types::DI;
 
 /// Represent DI target type.
@@ -18,30 +21,66 @@ impl<T: 'static> DITarget for T {}
 #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
 impl<T: Send + Sync + 'static> DITarget for T {}
 
+/// Represent a bound of futures returned by async DI apis. (`native-async` feature)
+/// It requires `Send` like [`DITarget`].
+#[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+pub trait MaybeSend {}
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+pub trait MaybeSend: Send {}
+
+#[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+impl<T> MaybeSend for T {}
+#[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+impl<T: Send> MaybeSend for T {}
+
 /// Add `di` methods for DI target types.
 pub trait DIPortal {
+    /// Whether a component is created per scope.
+    /// Otherwise a component is a singleton in the root container.
+    const SCOPED: bool = false;
+
     /// DI on a container.
     fn di_on(container: &DIContainer) -> DI<Self>
     where
         Self: Sized + DITarget,
     {
+        let container = if Self::SCOPED {
+            container
+        } else {
+            container.root()
+        };
         container.get_or_init(|| Self::create_for_di(container))
     }
 
-    /// DI on the global container.
-    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    /// DI on the current container, or the global container.
+    /// A component registered by [`DIContainer::register`] takes precedence.
     fn di() -> DI<Self>
     where
         Self: Sized + DITarget,
     {
-        Self::di_on(&INSTANCE)
+        let singleton = Self::static_singleton().filter(|_| !Self::SCOPED);
+        if let Some(c) = singleton.and_then(StaticSingleton::get) {
+            return c;
+        }
+        let generation = StaticSingleton::<Self>::generation();
+        let container = DIContainer::current();
+        // registrations take precedence like generated providers.
+        let c = container
+            .resolve_registered::<Self>()
+            .unwrap_or_else(|| Self::di_on(&container));
+        match singleton {
+            Some(singleton) => singleton.put(&container, generation, c),
+            None => c,
+        }
     }
-    #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
-    fn di() -> DI<Self>
+
+    /// Static cache of a singleton for `di()`. (generated by the `static-singletons` feature)
+    #[doc(hidden)]
+    fn static_singleton() -> Option<&'static StaticSingleton<Self>>
     where
         Self: Sized + DITarget,
     {
-        Self::di_on(INSTANCE.with(|i| i.clone()).as_ref())
+        None
     }
 
     /// Create new instance for DI.
@@ -49,43 +88,136 @@ pub trait DIPortal {
 }
 
 /// Add `di` methods for DI target types that needs async creation.
+#[cfg(not(feature = "native-async"))]
 #[cfg_attr(all(target_arch = "wasm32", not(feature = "multi-thread")), async_trait(?Send))]
 #[cfg_attr(
     any(not(target_arch = "wasm32"), feature = "multi-thread"),
     async_trait
 )]
 pub trait AsyncDIPortal {
+    /// Whether a component is created per scope.
+    /// Otherwise a component is a singleton in the root container.
+    const SCOPED: bool = false;
+
     /// DI on a container.
     async fn di_on(container: &DIContainer) -> DI<Self>
     where
         Self: Sized + DITarget,
     {
+        let container = if Self::SCOPED {
+            container
+        } else {
+            container.root()
+        };
         container
             .get_or_init_async(|| Self::create_for_di(container))
             .await
     }
 
-    /// DI on the global container.
-    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    /// DI on the current container, or the global container.
+    /// A component registered by [`DIContainer::register`] takes precedence.
     async fn di() -> DI<Self>
     where
         Self: Sized + DITarget,
     {
-        Self::di_on(&INSTANCE).await
+        let singleton = Self::static_singleton().filter(|_| !Self::SCOPED);
+        if let Some(c) = singleton.and_then(StaticSingleton::get) {
+            return c;
+        }
+        let generation = StaticSingleton::<Self>::generation();
+        let container = DIContainer::current();
+        // registrations take precedence like generated providers.
+        let c = match container.resolve_registered_async::<Self>().await {
+            Some(c) => c,
+            None => Self::di_on(&container).await,
+        };
+        match singleton {
+            Some(singleton) => singleton.put(&container, generation, c),
+            None => c,
+        }
     }
-    #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
-    async fn di() -> DI<Self>
+
+    /// Static cache of a singleton for `di()`. (generated by the `static-singletons` feature)
+    #[doc(hidden)]
+    fn static_singleton() -> Option<&'static StaticSingleton<Self>>
     where
         Self: Sized + DITarget,
     {
-        Self::di_on(INSTANCE.with(|i| i.clone()).as_ref()).await
+        None
     }
 
     /// Create new instance for DI.
     async fn create_for_di(container: &DIContainer) -> Self;
 }
 
+/// Add `di` methods for DI target types that needs async creation.
+#[cfg(feature = "native-async")]
+pub trait AsyncDIPortal {
+    /// Whether a component is created per scope.
+    /// Otherwise a component is a singleton in the root container.
+    const SCOPED: bool = false;
+
+    /// DI on a container.
+    fn di_on(container: &DIContainer) -> impl Future<Output = DI<Self>> + MaybeSend
+    where
+        Self: Sized + DITarget,
+    {
+        async move {
+            let container = if Self::SCOPED {
+                container
+            } else {
+                container.root()
+            };
+            container
+                .get_or_init_async(|| Self::create_for_di(container))
+                .await
+        }
+    }
+
+    /// DI on the current container, or the global container.
+    /// A component registered by [`DIContainer::register`] takes precedence.
+    fn di() -> impl Future<Output = DI<Self>> + MaybeSend
+    where
+        Self: Sized + DITarget,
+    {
+        async {
+            let singleton = Self::static_singleton().filter(|_| !Self::SCOPED);
+            if let Some(c) = singleton.and_then(StaticSingleton::get) {
+                return c;
+            }
+            let generation = StaticSingleton::<Self>::generation();
+            let container = DIContainer::current();
+            // registrations take precedence like generated providers.
+            let c = match container.resolve_registered_async::<Self>().await {
+                Some(c) => c,
+                None => Self::di_on(&container).await,
+            };
+            match singleton {
+                Some(singleton) => singleton.put(&container, generation, c),
+                None => c,
+            }
+        }
+    }
+
+    /// Static cache of a singleton for `di()`. (generated by the `static-singletons` feature)
+    #[doc(hidden)]
+    fn static_singleton() -> Option<&'static StaticSingleton<Self>>
+    where
+        Self: Sized + DITarget,
+    {
+        None
+    }
+
+    /// Create new instance for DI.
+    fn create_for_di(container: &DIContainer) -> impl Future<Output = Self> + MaybeSend;
+}
+
 /// Provides component instance for trait DI types.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a `DIProvider`",
+    label = "no synchronous provider",
+    note = "if `{Self}` creates its component asynchronously, add `#[inject(async)]` to the field"
+)]
 pub trait DIProvider {
     /// Target trait type.
     type Output: ?Sized;
@@ -93,18 +225,20 @@ pub trait DIProvider {
     /// DI on a container.
     fn di_on(container: &DIContainer) -> DI<Self::Output>;
 
-    /// DI on the global container.
-    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
-    fn di() -> DI<Self::Output> {
-        Self::di_on(&INSTANCE)
-    }
-    #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
+    /// DI on the current container, or the global container.
+    /// A component registered by [`DIContainer::register`] takes precedence.
     fn di() -> DI<Self::Output> {
-        Self::di_on(INSTANCE.with(|i| i.clone()).as_ref())
+        Self::di_on(&DIContainer::current())
     }
 }
 
 /// Provides component instance for trait DI types that needs async creation.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not an `AsyncDIProvider`",
+    label = "no asynchronous provider",
+    note = "remove `async` from `#[inject(..)]` if `{Self}` creates its component synchronously"
+)]
+#[cfg(not(feature = "native-async"))]
 #[cfg_attr(all(target_arch = "wasm32", not(feature = "multi-thread")), async_trait(?Send))]
 #[cfg_attr(
     any(not(target_arch = "wasm32"), feature = "multi-thread"),
@@ -117,14 +251,31 @@ pub trait AsyncDIProvider {
     /// DI on a container.
     async fn di_on(container: &DIContainer) -> DI<Self::Output>;
 
-    /// DI on the global container.
-    #[cfg(any(not(target_arch = "wasm32"), feature = "multi-thread"))]
+    /// DI on the current container, or the global container.
+    /// A component registered by [`DIContainer::register`] takes precedence.
     async fn di() -> DI<Self::Output> {
-        Self::di_on(&INSTANCE).await
+        Self::di_on(&DIContainer::current()).await
     }
-    #[cfg(all(target_arch = "wasm32", not(feature = "multi-thread")))]
-    async fn di() -> DI<Self::Output> {
-        Self::di_on(INSTANCE.with(|i| i.clone()).as_ref()).await
+}
+
+/// Provides component instance for trait DI types that needs async creation.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not an `AsyncDIProvider`",
+    label = "no asynchronous provider",
+    note = "remove `async` from `#[inject(..)]` if `{Self}` creates its component synchronously"
+)]
+#[cfg(feature = "native-async")]
+pub trait AsyncDIProvider {
+    /// Target trait type.
+    type Output: ?Sized;
+
+    /// DI on a container.
+    fn di_on(container: &DIContainer) -> impl Future<Output = DI<Self::Output>> + MaybeSend;
+
+    /// DI on the current container, or the global container.
+    /// A component registered by [`DIContainer::register`] takes precedence.
+    fn di() -> impl Future<Output = DI<Self::Output>> + MaybeSend {
+        async { Self::di_on(&DIContainer::current()).await }
     }
 }
 
@@ -162,6 +313,46 @@ mod tests {
             assert!(std::ptr::eq(hoge1, hoge2));
         }
 
+        struct ScopedHoge;
+        impl DIPortal for ScopedHoge {
+            const SCOPED: bool = true;
+            fn create_for_di(_container: &DIContainer) -> Self {
+                ScopedHoge {}
+            }
+        }
+
+        #[test]
+        fn test_scope() {
+            let root = DI::new(DIContainer::new());
+            let scope1 = DI::new(root.scope());
+            let scope2 = root.scope();
+
+            // singletons are shared with the root container.
+            let hoge1 = Hoge::di_on(&scope1).as_ref() as *const _;
+            let hoge2 = Hoge::di_on(&scope2).as_ref() as *const _;
+            assert!(std::ptr::eq(hoge1, hoge2));
+            assert!(root.get::<Hoge>().is_some());
+            assert!(scope1.get::<Hoge>().is_none());
+
+            // scoped components are created per scope.
+            let scoped1 = ScopedHoge::di_on(&scope1).as_ref() as *const _;
+            let scoped2 = ScopedHoge::di_on(&scope2).as_ref() as *const _;
+            assert!(!std::ptr::eq(scoped1, scoped2));
+            assert!(std::ptr::eq(
+                scoped1,
+                ScopedHoge::di_on(&scope1).as_ref() as *const _
+            ));
+
+            // disposed scopes drop their components.
+            scope1.dispose();
+            assert!(scope1.get::<ScopedHoge>().is_none());
+            assert!(root.get::<Hoge>().is_some());
+
+            // nested scopes share the root container.
+            let nested = scope1.scope();
+            assert!(std::ptr::eq(nested.root(), root.as_ref()));
+        }
+
         #[test]
         #[allow(non_snake_case)]
         fn test_same_instance_for_DIProvider() {
@@ -176,7 +367,7 @@ mod tests {
         use super::*;
 
         struct AsyncHoge;
-        #[async_trait]
+        #[cfg_attr(not(feature = "native-async"), async_trait)]
         impl AsyncDIPortal for AsyncHoge {
             async fn create_for_di(_container: &DIContainer) -> Self {
                 AsyncHoge {}
@@ -185,7 +376,7 @@ mod tests {
         impl FooI for AsyncHoge {}
 
         struct AsyncFooIProvider;
-        #[async_trait]
+        #[cfg_attr(not(feature = "native-async"), async_trait)]
         impl AsyncDIProvider for AsyncFooIProvider {
             type Output = dyn FooI;
             async fn di_on(container: &DIContainer) -> DI<Self::Output> {
@@ -212,5 +403,26 @@ mod tests {
             println!("check !!! {:?} {:?}", foo1, foo2);
             assert!(std::ptr::eq(foo1, foo2));
         }
+
+        static SLOW_CREATED: std::sync::atomic::AtomicUsize =
+            std::sync::atomic::AtomicUsize::new(0);
+
+        struct SlowHoge;
+        #[cfg_attr(not(feature = "native-async"), async_trait)]
+        impl AsyncDIPortal for SlowHoge {
+            async fn create_for_di(_container: &DIContainer) -> Self {
+                SLOW_CREATED.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                tokio::task::yield_now().await;
+                SlowHoge {}
+            }
+        }
+
+        #[tokio::test]
+        async fn test_single_flight() {
+            let c = DIContainer::new();
+            let (hoge1, hoge2) = crate::join!(SlowHoge::di_on(&c), SlowHoge::di_on(&c));
+            assert!(DI::ptr_eq(&hoge1, &hoge2));
+            assert_eq!(SLOW_CREATED.load(std::sync::atomic::Ordering::SeqCst), 1);
+        }
     }
 }
@@ -52,6 +52,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::let_unit_value)]
     fn test_usage() {
         struct Hoge {}
         impl Hoge {
@@ -64,6 +65,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::let_unit_value, clippy::arc_with_non_send_sync)]
     fn test_usage_for_trait() {
         trait HogeI {
             fn hello(&self);
@@ -0,0 +1,87 @@
+//! Panic safety of component creation.
+//!
+//! A component whose creation panics is not put into a container, so that the next resolution retries it.
+//! The panic is raised again with its original payload, and the dependency chain of the component
+//! is kept for the current thread, e.g. `Foo` -> `Bar` -> `Baz`. (see `DIContainer::last_panic_chain`)
+
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+thread_local! {
+    /// Type names of components being created on the current thread, outermost first.
+    static CHAIN: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    /// Whether the chain of a panic is already kept by an inner creation.
+    static REPORTED: Cell<bool> = const { Cell::new(false) };
+    /// The chain of the last panic in creation on the current thread.
+    static LAST_CHAIN: RefCell<Option<Vec<&'static str>>> = const { RefCell::new(None) };
+}
+
+/// Lock a mutex even if it is poisoned.
+///
+/// Data guarded in this crate is never left half-updated by a panic, so poisoning is ignored.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Create a component, and keep the dependency chain of a panic in it.
+pub(crate) fn create<R>(type_name: &'static str, f: impl FnOnce() -> R) -> R {
+    let _creating = Creating::enter(type_name);
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(v) => {
+            // a dependent may catch a reported panic of its dependency.
+            REPORTED.set(false);
+            v
+        }
+        Err(payload) => {
+            // only the innermost creation keeps the chain.
+            if !REPORTED.replace(true) {
+                let chain = CHAIN.with(|c| c.borrow().clone());
+                LAST_CHAIN.set(Some(chain));
+            }
+            resume_unwind(payload)
+        }
+    }
+}
+
+/// The chain of the last panic in creation on the current thread.
+pub(crate) fn last_chain() -> Option<Vec<&'static str>> {
+    LAST_CHAIN.with(|c| c.borrow().clone())
+}
+
+/// A component being created, which leaves the chain on drop even if the creation unwinds.
+struct Creating {
+    is_outermost: bool,
+}
+
+impl Creating {
+    fn enter(type_name: &'static str) -> Self {
+        CHAIN.with(|c| {
+            let mut c = c.borrow_mut();
+            c.push(type_name);
+            Creating {
+                is_outermost: c.len() == 1,
+            }
+        })
+    }
+}
+
+impl Drop for Creating {
+    fn drop(&mut self) {
+        CHAIN.with(|c| c.borrow_mut().pop());
+        if self.is_outermost {
+            REPORTED.set(false);
+        }
+    }
+}
+
+/// Create a component asynchronously. (see [`create`])
+///
+/// Creations of dependencies are polled in the poll of a dependent, so the chain is tracked on each poll.
+pub(crate) async fn create_async<F: Future>(type_name: &'static str, fut: F) -> F::Output {
+    let mut fut = std::pin::pin!(fut);
+    std::future::poll_fn(|cx| create(type_name, || fut.as_mut().poll(cx))).await
+}
@@ -40,7 +40,7 @@ mod app {
             self.hoge_repo
                 .get(id)
                 .await
-                .expect(format!("hoge not found for {}", id).as_str())
+                .unwrap_or_else(|| panic!("hoge not found for {}", id))
         }
     }
 }
@@ -96,7 +96,7 @@ mod repo {
         }
         async fn get(&self, id: u8) -> Option<Hoge> {
             println!("get from repository");
-            self.datas.lock().unwrap().get(&id).map(|v| v.clone())
+            self.datas.lock().unwrap().get(&id).cloned()
         }
     }
 }
@@ -69,6 +69,232 @@
 //!
 //! ```
 //!
+//! A trait in another module is injected by its path, e.g. `DI<dyn crate::service::FooI>` uses `crate::service::FooIProvider`.
+//! Re-export the provider from the module of the trait,
+//! or register it as the default provider of the trait by `default` and name it by `ProviderOf`.
+//!
+//! ```
+//! mod service {
+//!     pub trait FooI: portaldi::DITarget {}
+//!
+//!     pub type FooIProvider = portaldi::ProviderOf<dyn FooI>;
+//! }
+//!
+//! mod impls {
+//!     use portaldi::*;
+//!
+//!     #[derive(DIPortal)]
+//!     #[provide(crate::service::FooI, default)]
+//!     pub struct Foo {}
+//!
+//!     impl crate::service::FooI for Foo {}
+//! }
+//!
+//! #[derive(portaldi::DIPortal)]
+//! struct Hoge {
+//!   foo: portaldi::DI<dyn crate::service::FooI>, // crate::service::FooIProvider is used.
+//! }
+//!
+//! fn main() {
+//!     use portaldi::DIPortal;
+//!     Hoge::di();
+//! }
+//! ```
+//!
+//! ### Binding traits in a composition root
+//!
+//! Instead of `provide` on an implementation, you can bind a trait to an implementation with `bind!`.
+//! The implementation is resolved through its provider, so the wiring can live in one place.
+//!
+//! ```
+//! use portaldi::*;
+//!
+//! pub trait FooI: DITarget {}
+//!
+//! #[derive(DIPortal)] // FooProvider is generated.
+//! struct Foo { /* other deps */ }
+//!
+//! impl FooI for Foo {}
+//!
+//! bind!(dyn FooI => Foo); // FooIProvider is generated.
+//!
+//! // Use FooI component
+//! FooIProvider::di();
+//!
+//! ```
+//!
+//! ### Function dependencies
+//!
+//! When a free function needs dependencies, you can annotate it with `inject`.
+//! `DI<T>` parameters are resolved like fields, and removed from the signature.
+//!
+//! ```
+//! use portaldi::*;
+//!
+//! #[derive(DIPortal)]
+//! struct Foo { /* other deps */ }
+//!
+//! #[portaldi::inject]
+//! fn run(foo: DI<Foo>, name: &str) { /* ... */ }
+//!
+//! // Use function
+//! run("hoge");
+//! // Use function on a container
+//! run_on(&DIContainer::new(), "hoge");
+//!
+//! ```
+//!
+//! ### Current container
+//!
+//! `di()` resolves on the current container, which defaults to the global container.
+//! `with_container` sets the current container while a future runs, e.g. to use a request scope in async code.
+//!
+//! ```
+//! use portaldi::*;
+//!
+//! #[derive(DIPortal)]
+//! struct Foo { /* other deps */ }
+//!
+//! # async fn example() {
+//! let container = DI::new(DIContainer::new());
+//! let foo = with_container(&container, async { Foo::di() }).await;
+//! assert!(DI::ptr_eq(&foo, &container.get::<Foo>().unwrap()));
+//! # }
+//! ```
+//! With the `tokio` feature, the current container is a tokio task-local value.
+//! Otherwise it is a thread-local value that is set while the future is polled.
+//!
+//! ### Isolated tests
+//!
+//! Components in the global container are shared by all tests in a process.
+//! `#[portaldi::testing::test]` gives a test its own global container, so that tests don't leak components into each other.
+//! It's used by path, so that `use portaldi::*` doesn't conflict with `#[test]`.
+//!
+//! ```ignore
+//! #[portaldi::testing::test]
+//! fn test_foo() {
+//!     let foo = Foo::di(); // resolved on the container of this test.
+//! }
+//!
+//! #[portaldi::testing::test]
+//! #[tokio::test(flavor = "multi_thread")]
+//! async fn test_foo_async() {
+//!     let foo = Foo::di(); // resolved on the container of this test, on any worker.
+//! }
+//! ```
+//! `isolate_global` and `replace_global` do the same with a guard on the current thread,
+//! and `isolate_global_in` and `replace_global_in` for a future.
+//!
+//! ### Container builder
+//!
+//! `DIContainer::builder()` builds a container with pre-registered components,
+//! which take precedence over generated providers resolving on the container (or its scopes).
+//!
+//! ```
+//! use portaldi::*;
+//!
+//! pub trait FooI: DITarget {}
+//!
+//! #[derive(DIPortal)]
+//! #[provide(FooI)] // IntoTarget<dyn FooI> for Foo is also generated.
+//! struct Foo {}
+//!
+//! impl FooI for Foo {}
+//!
+//! pub struct Config { /* ... */ }
+//!
+//! let container = DIContainer::builder()
+//!     .instance(Config {})                                // a ready-made instance.
+//!     .bind::<dyn FooI, Foo>()                            // an implementation, which implements `IntoTarget`.
+//!     .factory::<dyn FooI>(|c| Foo::di_on(c))             // a factory.
+//!     .async_factory::<dyn FooI>(|c| Box::pin(async move { Foo::di_on(c) as DI<dyn FooI> })) // an async factory.
+//!     .build();
+//! ```
+//! A later registration of the same type replaces an earlier one.
+//! A type registered with an async factory must be resolved asynchronously.
+//!
+//! Factories can also be registered on an existing container at runtime,
+//! e.g. for an implementation chosen by a command line flag.
+//!
+//! ```
+//! # use portaldi::*;
+//! # pub trait FooI: DITarget {}
+//! # #[derive(DIPortal)]
+//! # #[provide(FooI)]
+//! # struct Foo {}
+//! # impl FooI for Foo {}
+//! let container = DIContainer::new();
+//! container.register::<dyn FooI>(|c| Foo::di_on(c));
+//! // container.register_async::<dyn FooI>(|c| Box::pin(async move { ... }));
+//!
+//! FooIProvider::di_on(&container); // resolved by the registered factory.
+//! ```
+//!
+//! ### Named bindings
+//!
+//! A `name` in `provide` (or `provider`) binds a target type to the name,
+//! so that a component can be chosen by a name given at runtime, e.g. from a config file.
+//! Bindings of all crates in a program are collected at program start.
+//!
+//! ```
+//! use portaldi::*;
+//!
+//! pub trait StorageI: DITarget {}
+//!
+//! #[derive(DIPortal)]
+//! #[provide(StorageI, name = "storage.s3")]
+//! struct S3Storage {}
+//!
+//! impl StorageI for S3Storage {}
+//!
+//! let container = DIContainer::new();
+//! let storage: Option<DI<dyn StorageI>> = container.resolve_named::<dyn StorageI>("storage.s3");
+//! assert!(storage.is_some());
+//!
+//! // list all named bindings.
+//! for binding in DIContainer::names() {
+//!     println!("{} => {}", binding.name(), binding.target());
+//! }
+//! ```
+//! Implementations of the same trait generate providers of the same name,
+//! so they are declared in their own modules, e.g. `#[provide(super::StorageI, name = "storage.local")]`.
+//! A name chooses its implementation even if the target type is registered on a container.
+//! A name is bound per target type, and an unknown name resolves to `None`.
+//! A component created asynchronously must be resolved by `resolve_named_async`, otherwise it panics.
+//! Bindings are indexed on first use, which panics if a name is bound to the same target type twice.
+//!
+//! ### Static singletons
+//!
+//! With the `static-singletons` feature, `#[derive(DIPortal)]` and `#[provider]` generate a per-type static,
+//! and `di()` on the global container reads a singleton from it without looking up the container.
+//! The static holds the instance in the global container, so `di_on(&DIContainer::global())` returns the same one.
+//! `di()` resolves a type registered by `DIContainer::register` by its registration as providers do,
+//! and a registration on the global container invalidates the statics, so `di()` never returns a stale instance.
+//! While a current container or a replaced global container is set, `di()` resolves on the container as usual.
+//! Scoped components and generic `DIPortal` implementations are not cached.
+//!
+//! ### Macro configuration
+//!
+//! Macros are configured by `[package.metadata.portaldi]` in `Cargo.toml`,
+//! or otherwise by the nearest `portaldi.toml` in the crate directory or its ancestors up to the workspace root.
+//!
+//! ```toml
+//! always-async = true               # generate AsyncDIPortal for all derived components.
+//! default-visibility = "pub(crate)" # visibility of generated providers. (default: "pub")
+//! default-lifetime = "scoped"       # lifetime of derived components, `#[singleton]` overrides it. (default: "singleton")
+//! provider-patterns = [
+//!     # `FooImpl` provides `dyn Foo`.
+//!     "^(\\w+)Impl$",
+//!     # `SqlUserRepo` provides `dyn Repo<User>`.
+//!     { pattern = "^Sql(\\w+)Repo$", target = "Repo<$1>" },
+//! ]
+//! ```
+//! Provider patterns apply to `#[derive(DIPortal)]` without `provide` and `#[provider]` without arguments,
+//! and `#[provide(none)]` opts out of them.
+//! Type arguments of a type are carried to a target without type arguments,
+//! e.g. `#[provider] impl DIPortal for CacheImpl<String>` provides `dyn Cache<String>`.
+//! `PORTALDI_ALWAYS_ASYNC` and `PORTALDI_PROVIDER_PATTERN` environment variables are still read for settings absent in the configuration.
+//!
 //! ### Manually component creation
 //!
 //! When you need a custom creation logic for a compoonent, you manually define a implementation for `DIPortal`.
@@ -132,15 +358,15 @@
 //!
 //! #### For async creation logic
 //! If a depencency has async custom creation logic, you manually define a implementation for `AsyncDIPortal`.
-//! Also you need anotate `inject` with `async` on the depencency field.
+//! A component that depends on it becomes async automatically, so `Hoge::di()` returns a future.
+//! A hand-written provider (implementing `DIProvider` or `AsyncDIProvider` directly) is used implicitly, too,
+//! and another provider can be specified by `#[inject(MyProvider)]` or `#[inject(MyProvider, async)]`.
 //!
 //! ```
-//! use portaldi::*;
-//! use async_trait::async_trait;
+//! use portaldi::*; // includes `async_trait`
 //!
 //! #[derive(DIPortal)]
 //! struct Hoge {
-//!   #[inject(async)]
 //!   foo: DI<Foo>, // FooProvider must be in this scope
 //!   // other deps
 //! }
@@ -162,17 +388,17 @@
 //! };
 //!
 //! ```
+//! With the `native-async` feature, async traits are native `async fn` traits instead of `async_trait`.
+//! Implement them without `#[async_trait]`, then futures are not boxed.
 //!
 //! #### For complex creation logic that involves other components.
 //! If a depencency has custom creation logic that needs other components, you manually define a factory component and implementation for `DIPortal`.
 //!
 //! ```
 //! use portaldi::*;
-//! use async_trait::async_trait;
 //!
 //! #[derive(DIPortal)]
 //! struct Hoge {
-//!   #[inject(async)]
 //!   foo: DI<Foo>,
 //!   // other deps
 //! }
@@ -50,7 +50,7 @@
 //! ```
 //!
 //! You may want to avoid this implementation imports.
-//! In that case, add provider barrel module with re-export to hide detailed imports.
+//! In that case, add a provider module with `portaldi::module!` to hide detailed imports.
 //!
 //!
 //! ```
@@ -83,9 +83,12 @@
 //!         impl BarI for Bar {}
 //!     }
 //!
-//!     mod providers {
-//!         pub use super::foo_impl::FooIProvider;
-//!         pub use super::bar_impl::BarIProvider;
+//!     // generates `mod providers` that re-exports FooIProvider and BarIProvider.
+//!     portaldi::module! {
+//!         providers {
+//!             dyn FooI from foo_impl,
+//!             dyn BarI from bar_impl,
+//!         }
 //!     }
 //!
 //!     use portaldi::*;
@@ -102,3 +105,49 @@
 //! ```
 //!
 //!
+//! A provider module can include other provider modules,
+//! and lists its bindings at runtime through `DIModule`.
+//!
+//! ```
+//! mod tips2 {
+//!     mod service {
+//!         use portaldi::*;
+//!
+//!         pub trait FooI: DITarget {}
+//!     }
+//!
+//!     mod infra {
+//!         mod foo_impl {
+//!             use portaldi::*;
+//!             use super::super::service::*;
+//!
+//!             #[derive(DIPortal)]
+//!             #[provide(FooI)]
+//!             pub struct Foo {}
+//!             impl FooI for Foo {}
+//!         }
+//!
+//!         portaldi::module! {
+//!             pub providers {
+//!                 dyn FooI from foo_impl,
+//!             }
+//!         }
+//!     }
+//!
+//!     portaldi::module! {
+//!         pub providers {
+//!             include infra::providers,
+//!         }
+//!     }
+//!
+//!     use portaldi::*;
+//!
+//!     fn print_bindings() {
+//!         for binding in providers::Module::bindings() {
+//!             println!("{} => {}", binding.target, binding.provider);
+//!         }
+//!     }
+//! }
+//!
+//! ```
+//!
@@ -0,0 +1,90 @@
+//! [actix-web](https://docs.rs/actix-web) integration.
+//!
+//! Handlers can take components with extractors, like `web::Data`.
+//!
+//! ```ignore
+//! async fn handler(
+//!     foo: Inject<FooProvider>,          // for a concrete type
+//!     bar: Inject<BarIProvider>,         // for a trait
+//!     baz: AsyncInject<BazProvider>,     // for a component that needs async creation
+//! ) -> String {
+//!     ...
+//! }
+//!
+//! let container = DI::new(DIContainer::new());
+//! App::new()
+//!     .app_data(web::Data::from(container))
+//!     .route("/", web::get().to(handler));
+//! ```
+//!
+//! Components are resolved on a request scope.
+//! If request extensions have a `DI<DIContainer>`, it is used as the request scope.
+//! Otherwise a scope of the container in app data (`web::Data<DIContainer>`, or the current container if absent)
+//! is created and inserted into the extensions, so that all extractors of a request share the same scope.
+//! The created scope is disposed when the request is dropped.
+
+use std::{future::Future, ops::Deref, pin::Pin};
+
+use ::actix_web::{dev::Payload, web, Error, FromRequest, HttpMessage, HttpRequest};
+
+use super::ScopeGuard;
+use crate::{AsyncDIProvider, DIContainer, DIProvider, DI};
+
+/// Get the request scope, or create it from the container in app data.
+pub fn request_scope(req: &HttpRequest) -> DI<DIContainer> {
+    if let Some(scope) = req.extensions().get::<DI<DIContainer>>() {
+        return scope.clone();
+    }
+    let container = req
+        .app_data::<web::Data<DIContainer>>()
+        .map(|data| data.clone().into_inner())
+        .unwrap_or_else(DIContainer::current);
+    let scope = DI::new(container.scope());
+    let mut extensions = req.extensions_mut();
+    extensions.insert(scope.clone());
+    // extensions are cleared when the request is dropped, even if it is cancelled.
+    extensions.insert(ScopeGuard::new(scope.clone()));
+    scope
+}
+
+/// Extractor that injects a component through a [`DIProvider`].
+pub struct Inject<P: DIProvider>(pub DI<P::Output>);
+
+impl<P: DIProvider> FromRequest for Inject<P> {
+    type Error = Error;
+    type Future = std::future::Ready<Result<Self, Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let scope = request_scope(req);
+        std::future::ready(Ok(Self(P::di_on(&scope))))
+    }
+}
+
+impl<P: DIProvider> Deref for Inject<P> {
+    type Target = DI<P::Output>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Extractor that injects a component through an [`AsyncDIProvider`].
+pub struct AsyncInject<P: AsyncDIProvider>(pub DI<P::Output>);
+
+impl<P: AsyncDIProvider + 'static> FromRequest for AsyncInject<P> {
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Error>>>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let scope = request_scope(req);
+        Box::pin(async move { Ok(Self(P::di_on(&scope).await)) })
+    }
+}
+
+impl<P: AsyncDIProvider> Deref for AsyncInject<P> {
+    type Target = DI<P::Output>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -0,0 +1,114 @@
+//! [axum](https://docs.rs/axum) integration.
+//!
+//! Handlers can take components with extractors instead of calling `di()`.
+//!
+//! ```ignore
+//! async fn handler(
+//!     Inject(foo): Inject<FooProvider>,          // for a concrete type
+//!     Inject(bar): Inject<BarIProvider>,         // for a trait
+//!     AsyncInject(baz): AsyncInject<BazProvider>, // for a component that needs async creation
+//! ) -> String {
+//!     ...
+//! }
+//! ```
+//!
+//! Components are resolved on a request scope.
+//! If request extensions have a `DI<DIContainer>` (e.g. inserted by `integration::tower::DIScopeLayer`),
+//! it is used as the request scope.
+//! Otherwise a scope of the container in router state ([`DIState`], or the current container for a router without state)
+//! is created and inserted into the extensions, so that all extractors of a request share the same scope.
+
+use std::{convert::Infallible, ops::Deref};
+
+use ::axum::{
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
+
+use crate::{AsyncDIProvider, DIContainer, DIProvider, DI};
+
+/// Router state that holds an application container.
+///
+/// A router without state uses the current container, like the actix integration.
+/// (see `DIContainer::current`)
+/// For custom state, implement `FromRef<YourState>` for this type.
+#[derive(Debug, Clone)]
+pub struct DIState(pub DI<DIContainer>);
+
+impl Default for DIState {
+    fn default() -> Self {
+        Self(DIContainer::current())
+    }
+}
+
+impl FromRef<()> for DIState {
+    fn from_ref(_: &()) -> Self {
+        Self::default()
+    }
+}
+
+/// Get the request scope, or create it from the container in router state.
+pub fn request_scope<S>(parts: &mut Parts, state: &S) -> DI<DIContainer>
+where
+    DIState: FromRef<S>,
+{
+    if let Some(scope) = parts.extensions.get::<DI<DIContainer>>() {
+        return scope.clone();
+    }
+    let DIState(container) = DIState::from_ref(state);
+    let scope = DI::new(container.scope());
+    parts.extensions.insert(scope.clone());
+    scope
+}
+
+/// Extractor that injects a component through a [`DIProvider`].
+pub struct Inject<P: DIProvider>(pub DI<P::Output>);
+
+impl<P, S> FromRequestParts<S> for Inject<P>
+where
+    P: DIProvider,
+    P::Output: Send + Sync,
+    S: Send + Sync,
+    DIState: FromRef<S>,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let scope = request_scope(parts, state);
+        Ok(Self(P::di_on(&scope)))
+    }
+}
+
+impl<P: DIProvider> Deref for Inject<P> {
+    type Target = DI<P::Output>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Extractor that injects a component through an [`AsyncDIProvider`].
+pub struct AsyncInject<P: AsyncDIProvider>(pub DI<P::Output>);
+
+impl<P, S> FromRequestParts<S> for AsyncInject<P>
+where
+    P: AsyncDIProvider,
+    P::Output: Send + Sync,
+    S: Send + Sync,
+    DIState: FromRef<S>,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let scope = request_scope(parts, state);
+        Ok(Self(P::di_on(&scope).await))
+    }
+}
+
+impl<P: AsyncDIProvider> Deref for AsyncInject<P> {
+    type Target = DI<P::Output>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
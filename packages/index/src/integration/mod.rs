@@ -0,0 +1,33 @@
+//! Integrations with other libraries, enabled by features.
+
+#[cfg(feature = "actix")]
+pub mod actix;
+
+#[cfg(feature = "axum")]
+pub mod axum;
+
+#[cfg(feature = "tower")]
+pub mod tower;
+
+#[cfg(any(feature = "actix", feature = "tower"))]
+use crate::{DIContainer, DI};
+
+/// Request scope, which is disposed when dropped,
+/// so that a scope is disposed even if a request is cancelled.
+#[cfg(any(feature = "actix", feature = "tower"))]
+#[derive(Debug)]
+pub(crate) struct ScopeGuard(DI<DIContainer>);
+
+#[cfg(any(feature = "actix", feature = "tower"))]
+impl ScopeGuard {
+    pub(crate) fn new(scope: DI<DIContainer>) -> Self {
+        Self(scope)
+    }
+}
+
+#[cfg(any(feature = "actix", feature = "tower"))]
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        self.0.dispose();
+    }
+}
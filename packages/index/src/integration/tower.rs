@@ -0,0 +1,109 @@
+//! [tower](https://docs.rs/tower) integration.
+//!
+//! [`DIScopeLayer`] opens a scope of an application container per request.
+//! The scope is inserted into request extensions as `DI<DIContainer>`,
+//! and disposed when the response future completes or is dropped (e.g. by a cancelled request).
+//!
+//! ```ignore
+//! let service = ServiceBuilder::new()
+//!     .layer(DIScopeLayer::new(container))
+//!     .service(service_fn(|req: Request<Body>| async move {
+//!         let scope = req.extensions().get::<DI<DIContainer>>().unwrap();
+//!         let foo = FooProvider::di_on(scope);
+//!         ...
+//!     }));
+//! ```
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use ::tower_layer::Layer;
+use ::tower_service::Service;
+use pin_project_lite::pin_project;
+
+use super::ScopeGuard;
+use crate::{DIContainer, DI};
+
+/// Layer that opens a scope per request.
+#[derive(Debug, Clone)]
+pub struct DIScopeLayer {
+    container: DI<DIContainer>,
+}
+
+impl DIScopeLayer {
+    /// Create a layer that opens scopes of a container.
+    pub fn new(container: DI<DIContainer>) -> Self {
+        Self { container }
+    }
+}
+
+impl Default for DIScopeLayer {
+    /// Create a layer that opens scopes of the global container.
+    fn default() -> Self {
+        Self::new(DIContainer::global())
+    }
+}
+
+impl<S> Layer<S> for DIScopeLayer {
+    type Service = DIScope<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DIScope {
+            inner,
+            container: self.container.clone(),
+        }
+    }
+}
+
+/// Service that opens a scope per request. See [`DIScopeLayer`].
+#[derive(Debug, Clone)]
+pub struct DIScope<S> {
+    inner: S,
+    container: DI<DIContainer>,
+}
+
+impl<S, B> Service<::http::Request<B>> for DIScope<S>
+where
+    S: Service<::http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = DIScopeFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: ::http::Request<B>) -> Self::Future {
+        let scope = DI::new(self.container.scope());
+        req.extensions_mut().insert(scope.clone());
+        DIScopeFuture {
+            inner: self.inner.call(req),
+            scope: Some(ScopeGuard::new(scope)),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`DIScope`], which disposes the scope on completion or drop.
+    pub struct DIScopeFuture<F> {
+        #[pin]
+        inner: F,
+        scope: Option<ScopeGuard>,
+    }
+}
+
+impl<F: Future> Future for DIScopeFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let output = std::task::ready!(this.inner.poll(cx));
+        // dispose the scope before the response is handed over.
+        this.scope.take();
+        Poll::Ready(output)
+    }
+}
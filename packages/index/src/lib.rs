@@ -4,7 +4,10 @@
 //!
 //! * Natively async support
 //!   * Support for asynchronous component creation.
+//!   * A component becomes async automatically when any dependency is async.
 //!   * Components and traits must be `thread-safe` (`Sync + Send`) in non Wasm target.
+//!   * Native `async fn` in traits without boxing by the `native-async` feature.
+//!   * Concurrent resolution of async dependencies. (with [`join!`])
 //!
 //! * Ergonomic apis
 //!   * In most cases, you can use target types directly instead of using containers.  
@@ -26,8 +29,29 @@
 //!   }
 //!   ```
 //!
+//! * Integrations (by features)
+//!   * `actix`: extractors that inject components on a request scope. (`portaldi::integration::actix`)
+//!   * `axum`: extractors that inject components on a request scope. (`portaldi::integration::axum`)
+//!   * `tower`: a layer that opens a scope per request. (`portaldi::integration::tower`)
+//!   * `tokio`: the current container of [`with_container`] and the global container of [`replace_global_in`] are tokio task-local values instead of thread-local values.
+//!   * `static-singletons`: singletons on the global container are cached in per-type statics. (see [`StaticSingleton`])
+//!
+
+pub use portaldi_core::{
+    builder::*, container::*, current::*, join, module::*, named::*, resolver::*, singleton::*,
+    traits::*, types::*,
+};
+pub use portaldi_macros::{
+    bind, def_async_di_provider, def_di_provider, di, inject, module, provider, DIPortal,
+};
+
+/// Re-export for async implementations, so that generated code doesn't require `async-trait` dependency.
+pub use async_trait::async_trait;
 
-pub use portaldi_core::{container::*, traits::*, types::*};
-pub use portaldi_macros::*;
+/// Re-export for named bindings, so that generated code doesn't require `inventory` dependency.
+#[doc(hidden)]
+pub use portaldi_core::inventory;
 
 pub mod docs;
+pub mod integration;
+pub mod testing;
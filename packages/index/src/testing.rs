@@ -0,0 +1,6 @@
+//! Test utilities.
+//!
+//! They are not re-exported at the crate root, so that `use portaldi::*` doesn't conflict with `#[test]`.
+//! Use them by path, e.g. `#[portaldi::testing::test]`.
+
+pub use portaldi_macros::test;
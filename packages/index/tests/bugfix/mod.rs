@@ -3,12 +3,15 @@ use crate::common::*;
 #[test]
 fn test_di() {}
 
+#[allow(non_camel_case_types, dead_code)]
 pub struct foo;
 
+#[allow(dead_code)]
 #[derive(DIPortal)]
 struct Hoge {
     foo: DI<Foo>, // unit struct と 同じフィールド名だと コンパイルエラーになっていたのを修正
 }
 
+#[allow(dead_code)]
 #[derive(DIPortal)]
 struct Foo {}
@@ -0,0 +1,7 @@
+use portaldi::*;
+
+pub struct Foo {}
+
+def_async_di_provider!(Foo);
+
+fn main() {}
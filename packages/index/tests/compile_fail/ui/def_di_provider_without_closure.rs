@@ -0,0 +1,7 @@
+use portaldi::*;
+
+pub struct Foo {}
+
+def_di_provider!(Foo, Foo {});
+
+fn main() {}
@@ -0,0 +1,11 @@
+use portaldi::*;
+
+#[derive(DIPortal)]
+struct Hoge {
+    foo: Foo,
+}
+
+#[derive(DIPortal)]
+struct Foo {}
+
+fn main() {}
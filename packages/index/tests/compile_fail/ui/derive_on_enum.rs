@@ -0,0 +1,8 @@
+use portaldi::*;
+
+#[derive(DIPortal)]
+enum Hoge {
+    A,
+}
+
+fn main() {}
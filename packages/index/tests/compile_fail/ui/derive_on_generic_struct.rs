@@ -0,0 +1,8 @@
+use portaldi::*;
+
+#[derive(DIPortal)]
+struct Hoge<T: DITarget> {
+    foo: DI<T>,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use portaldi::*;
+
+#[derive(DIPortal)]
+struct Foo {}
+
+fn main() {
+    di![Foo<String bool>];
+}
@@ -0,0 +1,16 @@
+use portaldi::*;
+
+pub struct Foo;
+
+#[provider(Self)]
+#[async_trait::async_trait]
+impl AsyncDIPortal for Foo {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        Foo
+    }
+}
+
+#[portaldi::inject]
+fn run(#[inject(async)] _foo: DI<Foo>) {}
+
+fn main() {}
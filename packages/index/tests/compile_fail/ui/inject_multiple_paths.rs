@@ -0,0 +1,12 @@
+use portaldi::*;
+
+#[derive(DIPortal)]
+struct Hoge {
+    #[inject(Foo, FooProvider)]
+    foo: DI<Foo>,
+}
+
+#[derive(DIPortal)]
+struct Foo {}
+
+fn main() {}
@@ -0,0 +1,11 @@
+mod service {
+    pub trait FooI: portaldi::DITarget {}
+}
+
+portaldi::module! {
+    providers {
+        dyn service::FooI,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use portaldi::*;
+
+mod a {
+    pub trait FooI: portaldi::DITarget {}
+}
+mod b {
+    pub trait FooI: portaldi::DITarget {}
+}
+
+#[derive(DIPortal)]
+#[provide(a::FooI, b::FooI)]
+struct Foo {}
+impl a::FooI for Foo {}
+impl b::FooI for Foo {}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use portaldi::*;
+
+pub trait FooI: DITarget {}
+
+#[derive(DIPortal)]
+#[provide(FooI, name = "foo", default)]
+struct Foo {}
+impl FooI for Foo {}
+
+fn main() {}
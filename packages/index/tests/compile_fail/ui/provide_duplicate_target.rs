@@ -0,0 +1,11 @@
+use portaldi::*;
+
+pub trait FooI: DITarget {}
+
+#[derive(DIPortal)]
+#[provide(FooI)]
+#[provide(FooI)]
+struct Foo {}
+impl FooI for Foo {}
+
+fn main() {}
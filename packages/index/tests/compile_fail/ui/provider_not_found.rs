@@ -0,0 +1,10 @@
+use portaldi::*;
+
+pub trait FooI: DITarget {}
+
+#[derive(DIPortal)]
+struct Hoge {
+    foo: DI<dyn FooI>,
+}
+
+fn main() {}
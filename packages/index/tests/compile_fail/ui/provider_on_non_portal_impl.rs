@@ -0,0 +1,10 @@
+use portaldi::*;
+
+pub trait FooI: DITarget {}
+
+struct Foo {}
+
+#[provider(FooI)]
+impl FooI for Foo {}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use portaldi::*;
+
+pub trait FooI: DITarget {}
+
+struct Foo {}
+impl FooI for Foo {}
+
+#[provider(FooI)]
+impl DIPortal for Foo {}
+
+fn main() {}
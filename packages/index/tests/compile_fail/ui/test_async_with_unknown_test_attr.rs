@@ -0,0 +1,5 @@
+#[portaldi::testing::test]
+#[hoge::test]
+async fn test_hoge() {}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[portaldi::testing::test]
+async fn test_hoge() {}
+
+fn main() {}
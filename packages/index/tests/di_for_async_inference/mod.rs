@@ -0,0 +1,124 @@
+use crate::common::*;
+
+#[tokio::test]
+async fn test_di() {
+    // async by an async dependency, without annotations
+    let hoge = Hoge::di().await;
+    assert!(ptr_eq(hoge.foo.as_ref(), Foo::di().await.as_ref()));
+    assert!(ptr_eq(hoge.bar.as_ref(), BarProvider::di().await.as_ref()));
+
+    // async transitively
+    let piyo = Piyo::di().await;
+    assert!(ptr_eq(piyo.hoge.as_ref(), hoge.as_ref()));
+}
+
+#[test]
+fn test_di_sync() {
+    // sync without async dependencies
+    let fuga = Fuga::di();
+    assert!(ptr_eq(fuga.baz.as_ref(), BazProvider::di().as_ref()));
+    assert!(ptr_eq(fuga.qux.as_ref(), QuxProvider::di().as_ref()));
+
+    // sync by a hand-written DIProvider, without `#[inject]`
+    let hogera = Hogera::di();
+    assert!(ptr_eq(hogera.qux.as_ref(), QuxProvider::di().as_ref()));
+}
+
+#[tokio::test]
+async fn test_di_hand_written_async() {
+    // async by a hand-written AsyncDIProvider, without `#[inject]`
+    let hogehoge = Hogehoge::di().await;
+    assert!(ptr_eq(hogehoge.qux.as_ref(), QuxProvider::di().as_ref()));
+    assert!(ptr_eq(hogehoge.quux.as_ref(), QuuxProvider::di().await.as_ref()));
+
+    // bound through a hand-written provider
+    let corge = CorgeProvider::di().await;
+    assert!(ptr_eq(corge.as_ref(), hogehoge.quux.as_ref()));
+}
+
+#[derive(DIPortal)]
+struct Hoge {
+    foo: DI<Foo>,
+    bar: DI<dyn Bar>,
+    _baz: DI<dyn Baz>,
+}
+
+#[derive(DIPortal)]
+struct Piyo {
+    hoge: DI<Hoge>,
+}
+
+#[derive(DIPortal)]
+struct Hogera {
+    qux: DI<Qux>,
+}
+
+#[derive(DIPortal)]
+struct Hogehoge {
+    qux: DI<Qux>,
+    quux: DI<Quux>,
+}
+
+#[derive(DIPortal)]
+struct Fuga {
+    baz: DI<dyn Baz>,
+    // di by manual Provider
+    #[inject(QuxProvider)]
+    qux: DI<Qux>,
+}
+
+pub struct Foo {}
+
+#[provider(Self)]
+#[async_trait]
+impl AsyncDIPortal for Foo {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        Foo {}
+    }
+}
+
+pub trait Bar: DITarget {}
+
+struct BarImpl {}
+impl Bar for BarImpl {}
+
+def_async_di_provider!(dyn Bar, |_c| async { BarImpl {} });
+
+pub trait Baz: DITarget {}
+
+// a trait provider on a derived struct is sync or async by its own dependencies.
+#[derive(DIPortal)]
+#[provide(Baz)]
+struct BazImpl {
+    #[inject(QuxProvider)]
+    _qux: DI<Qux>,
+}
+impl Baz for BazImpl {}
+
+struct Qux {}
+
+struct QuxProvider;
+impl DIProvider for QuxProvider {
+    type Output = Qux;
+
+    fn di_on(container: &DIContainer) -> DI<Qux> {
+        container.get_or_init(|| Qux {})
+    }
+}
+
+struct Quux {}
+
+struct QuuxProvider;
+#[async_trait]
+impl AsyncDIProvider for QuuxProvider {
+    type Output = Quux;
+
+    async fn di_on(container: &DIContainer) -> DI<Quux> {
+        container.get_or_init_async(|| async { Quux {} }).await
+    }
+}
+
+pub trait Corge: DITarget {}
+impl Corge for Quux {}
+
+bind!(dyn Corge => Quux);
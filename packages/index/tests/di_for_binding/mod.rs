@@ -0,0 +1,157 @@
+use crate::common::*;
+
+use service::*;
+
+#[test]
+fn test_di() {
+    let hoge = Hoge::di();
+    assert!(ptr_eq(hoge.foo.as_ref(), FooIProvider::di().as_ref()));
+    assert!(std::ptr::addr_eq(
+        hoge.foo.as_ref(),
+        infra::Foo::di().as_ref()
+    ));
+    assert!(std::ptr::addr_eq(
+        hoge.bar.as_ref(),
+        infra::Foo::di().as_ref()
+    ));
+    assert_eq!(hoge.baz.name(), "third party");
+    assert!(std::ptr::addr_eq(
+        hoge.primary_baz.target().as_ref(),
+        infra::Baz::di().as_ref()
+    ));
+}
+
+#[tokio::test]
+async fn test_async_di() {
+    let hoge = AsyncHoge::di().await;
+    assert!(std::ptr::addr_eq(
+        hoge.qux.as_ref(),
+        infra::Qux::di().await.as_ref()
+    ));
+    assert!(std::ptr::addr_eq(
+        hoge.tagged_qux.target().as_ref(),
+        infra::Qux::di().await.as_ref()
+    ));
+}
+
+#[tokio::test]
+async fn test_inferred_async_di() {
+    // async by the bound implementation, without `async`.
+    let hoge = InferredAsyncHoge::di().await;
+    assert!(std::ptr::addr_eq(
+        hoge.quux.as_ref(),
+        infra::Qux::di().await.as_ref()
+    ));
+    assert!(std::ptr::addr_eq(
+        hoge.tagged_quux.target().as_ref(),
+        infra::Qux::di().await.as_ref()
+    ));
+}
+
+#[tokio::test]
+async fn test_builder_bind() {
+    let c = DIContainer::builder()
+        .bind::<dyn FooI, infra::Foo>()
+        .bind_async::<dyn QuxI, infra::Qux>()
+        .build();
+    assert!(std::ptr::addr_eq(
+        FooIProvider::di_on(&c).as_ref(),
+        infra::Foo::di_on(&c).as_ref()
+    ));
+    assert!(std::ptr::addr_eq(
+        QuxIProvider::di_on(&c).await.as_ref(),
+        infra::Qux::di_on(&c).await.as_ref()
+    ));
+}
+
+#[derive(DIPortal)]
+struct Hoge {
+    foo: DI<dyn FooI>,
+    bar: DI<dyn BarI<String>>,
+    baz: DI<dyn BazI>,
+    primary_baz: DI<Tagged<dyn BazI, Primary>>,
+}
+
+#[derive(DIPortal)]
+struct AsyncHoge {
+    #[inject(async)]
+    qux: DI<dyn QuxI>,
+    #[inject(async)]
+    tagged_qux: DI<Tagged<dyn QuxI, Primary>>,
+}
+
+#[derive(DIPortal)]
+struct InferredAsyncHoge {
+    quux: DI<dyn QuuxI>,
+    tagged_quux: DI<Tagged<dyn QuuxI, Primary>>,
+}
+
+pub struct Primary;
+
+// composition root
+bind!(dyn FooI => infra::Foo);
+bind!(dyn service::BarI<String> => infra::Foo);
+bind!(dyn BazI => third_party::ThirdParty);
+bind!(dyn BazI => infra::Baz, tag = Primary);
+bind!(dyn QuxI => async infra::Qux);
+bind!(dyn QuxI => async infra::Qux, tag = Primary);
+bind!(dyn QuuxI => infra::Qux);
+bind!(dyn QuuxI => infra::Qux, tag = Primary);
+
+mod service {
+    use crate::common::*;
+
+    pub trait FooI: DITarget {}
+    pub trait BarI<T>: DITarget {}
+    pub trait BazI: DITarget {
+        fn name(&self) -> &str;
+    }
+    pub trait QuxI: DITarget {}
+    pub trait QuuxI: DITarget {}
+}
+
+// implementations don't know the traits they are bound to.
+pub mod infra {
+    use super::service::*;
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    pub struct Foo;
+    impl FooI for Foo {}
+    impl BarI<String> for Foo {}
+
+    #[derive(DIPortal)]
+    pub struct Baz;
+    impl BazI for Baz {
+        fn name(&self) -> &str {
+            "baz"
+        }
+    }
+
+    pub struct Qux;
+    impl QuxI for Qux {}
+    impl QuuxI for Qux {}
+
+    #[provider(Self)]
+    #[async_trait]
+    impl AsyncDIPortal for Qux {
+        async fn create_for_di(_container: &DIContainer) -> Self {
+            Qux
+        }
+    }
+}
+
+// a type that doesn't implement DIPortal, e.g. of another crate.
+mod third_party {
+    use super::service::*;
+    use crate::common::*;
+
+    pub struct ThirdParty(pub String);
+    impl BazI for ThirdParty {
+        fn name(&self) -> &str {
+            &self.0
+        }
+    }
+
+    def_di_provider!(ThirdParty, |_| ThirdParty("third party".to_string()));
+}
@@ -0,0 +1,169 @@
+use crate::common::*;
+
+#[test]
+fn test_default() {
+    let c = DIContainer::builder().build();
+    let hoge = Hoge::di_on(&c);
+    assert_eq!(hoge.foo.name(), "foo");
+    assert_eq!(hoge.config.name, "default");
+}
+
+#[test]
+fn test_instance_and_bind() {
+    let c = DI::new(
+        DIContainer::builder()
+            .instance(Config { name: "custom" })
+            .bind_with::<dyn FooI, alt::AltFoo>(|c| c)
+            .build(),
+    );
+    let hoge = Hoge::di_on(&c);
+    assert_eq!(hoge.foo.name(), "alt");
+    assert_eq!(hoge.config.name, "custom");
+    assert!(ptr_eq(hoge.config.as_ref(), c.get::<Config>().unwrap().as_ref()));
+
+    // a registered component is a singleton.
+    assert!(ptr_eq(hoge.foo.as_ref(), FooIProvider::di_on(&c).as_ref()));
+    assert!(std::ptr::addr_eq(
+        DI::as_ptr(&hoge.foo),
+        DI::as_ptr(&alt::AltFoo::di_on(&c))
+    ));
+
+    // scopes use registrations of the parent.
+    let scope = c.scope();
+    assert!(ptr_eq(hoge.foo.as_ref(), FooIProvider::di_on(&scope).as_ref()));
+}
+
+#[test]
+fn test_instance_replaced() {
+    let c = DIContainer::builder()
+        .instance(Config { name: "first" })
+        .instance(Config { name: "second" })
+        .build();
+    assert_eq!(c.get::<Config>().unwrap().name, "second");
+    assert_eq!(ConfigProvider::di_on(&c).name, "second");
+}
+
+#[test]
+fn test_instance_after_dispose() {
+    let c = DIContainer::builder()
+        .instance(Config { name: "custom" })
+        .build();
+    let config = ConfigProvider::di_on(&c);
+    c.dispose();
+    assert!(ptr_eq(config.as_ref(), ConfigProvider::di_on(&c).as_ref()));
+    assert_eq!(Hoge::di_on(&c).config.name, "custom");
+}
+
+#[test]
+fn test_factory() {
+    let c = DIContainer::builder()
+        .factory::<dyn FooI>(|_| DI::new(alt::AltFoo {}))
+        .factory::<Config>(|_| DI::new(Config { name: "factory" }))
+        .build();
+    let hoge = Hoge::di_on(&c);
+    assert_eq!(hoge.foo.name(), "alt");
+    assert_eq!(hoge.config.name, "factory");
+    assert!(ptr_eq(hoge.foo.as_ref(), FooIProvider::di_on(&c).as_ref()));
+    assert!(ptr_eq(hoge.config.as_ref(), ConfigProvider::di_on(&c).as_ref()));
+}
+
+#[tokio::test]
+async fn test_async_bind() {
+    let c = DIContainer::builder()
+        .bind_async_with::<dyn BarI, alt::AltBar>(|c| c)
+        .build();
+    let hoge = AsyncHoge::di_on(&c).await;
+    assert_eq!(hoge.bar.name(), "alt");
+    assert!(ptr_eq(hoge.bar.as_ref(), BarIProvider::di_on(&c).await.as_ref()));
+}
+
+#[tokio::test]
+async fn test_async_factory() {
+    let c = DIContainer::builder()
+        .async_factory::<dyn BarI>(|c| {
+            Box::pin(async move { alt::AltBar::di_on(c).await as DI<dyn BarI> })
+        })
+        .build();
+    let hoge = AsyncHoge::di_on(&c).await;
+    assert_eq!(hoge.bar.name(), "alt");
+    assert!(ptr_eq(hoge.bar.as_ref(), BarIProvider::di_on(&c).await.as_ref()));
+}
+
+#[derive(DIPortal)]
+struct Hoge {
+    foo: DI<dyn FooI>,
+    config: DI<Config>,
+}
+
+pub struct Config {
+    name: &'static str,
+}
+
+def_di_provider!(Config, |_| Config { name: "default" });
+
+pub trait FooI: DITarget {
+    fn name(&self) -> &'static str;
+}
+
+#[derive(DIPortal)]
+#[provide(FooI)]
+struct Foo {}
+
+impl FooI for Foo {
+    fn name(&self) -> &'static str {
+        "foo"
+    }
+}
+
+#[derive(DIPortal)]
+struct AsyncHoge {
+    bar: DI<dyn BarI>,
+}
+
+pub trait BarI: DITarget {
+    fn name(&self) -> &'static str;
+}
+
+pub struct Bar {}
+
+#[provider(BarI)]
+#[async_trait]
+impl AsyncDIPortal for Bar {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        Bar {}
+    }
+}
+
+impl BarI for Bar {
+    fn name(&self) -> &'static str {
+        "bar"
+    }
+}
+
+mod alt {
+    use super::*;
+
+    #[derive(DIPortal)]
+    pub struct AltFoo {}
+
+    impl FooI for AltFoo {
+        fn name(&self) -> &'static str {
+            "alt"
+        }
+    }
+
+    pub struct AltBar {}
+
+    #[async_trait]
+    impl AsyncDIPortal for AltBar {
+        async fn create_for_di(_container: &DIContainer) -> Self {
+            AltBar {}
+        }
+    }
+
+    impl BarI for AltBar {
+        fn name(&self) -> &'static str {
+            "alt"
+        }
+    }
+}
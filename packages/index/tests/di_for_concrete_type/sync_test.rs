@@ -6,7 +6,7 @@ use crate::common::*;
 fn test_di() {
     assert!(Hoge::di() == Hoge::di());
 
-    Yah3StringUnitProvider::di();
+    Yah3_GString_S_U_EProvider::di();
     di![Yah3<String, ()>];
 
     let c = &DIContainer::new();
@@ -74,6 +74,7 @@ def_di_provider!(Tagged<Yah, String>, |c| {
     Tagged::wrap(di![Yah on c])
 });
 
+#[allow(dead_code)]
 pub struct Yah2<A, B> {
     a: PhantomData<A>,
     b: PhantomData<B>,
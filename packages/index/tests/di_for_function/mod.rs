@@ -0,0 +1,140 @@
+use crate::common::*;
+
+use foo::*;
+
+#[test]
+fn test_di() {
+    let foo_i = run(1, (2, 3));
+    assert!(ptr_eq(foo_i.as_ref(), FooIProvider::di().as_ref()));
+
+    let c = DIContainer::new();
+    let foo_i = run_on(&c, 1, (2, 3));
+    assert!(ptr_eq(foo_i.as_ref(), FooIProvider::di_on(&c).as_ref()));
+    assert!(!ptr_eq(foo_i.as_ref(), FooIProvider::di().as_ref()));
+}
+
+#[test]
+fn test_attrs_on_outer_fn() {
+    // `#[deprecated]` is put only on `run_deprecated`.
+    let foo_i = run_deprecated_on(&DIContainer::new());
+    #[allow(deprecated)]
+    let global_foo_i = run_deprecated();
+    assert!(!ptr_eq(foo_i.as_ref(), global_foo_i.as_ref()));
+}
+
+#[tokio::test]
+async fn test_async_di() {
+    let (bar, name) = run_async("job".to_string()).await;
+    assert!(ptr_eq(bar.as_ref(), AsyncBar::di().await.as_ref()));
+    assert_eq!(name, "job");
+
+    let c = DIContainer::new();
+    let (bar, _) = run_async_on(&c, "job".to_string()).await;
+    assert!(ptr_eq(bar.as_ref(), AsyncBar::di_on(&c).await.as_ref()));
+}
+
+#[tokio::test]
+async fn test_inferred_async_di() {
+    let bar = run_inferred().await;
+    assert!(ptr_eq(bar.as_ref(), AsyncBar::di().await.as_ref()));
+}
+
+#[tokio::test]
+async fn test_async_di_concurrently() {
+    // both components wait for each other, so they are resolved only concurrently.
+    let (async_baz, async_qux) =
+        tokio::time::timeout(std::time::Duration::from_secs(5), run_concurrently())
+            .await
+            .expect("async parameters are resolved sequentially");
+    assert!(ptr_eq(async_baz.as_ref(), AsyncBaz::di().await.as_ref()));
+    assert!(ptr_eq(async_qux.as_ref(), AsyncQux::di().await.as_ref()));
+}
+
+#[portaldi::inject]
+fn run(foo_i: DI<dyn FooI>, a: u8, (b, c): (u8, u8), _foo: DI<Foo>) -> DI<dyn FooI> {
+    assert_eq!(a + b + c, 6);
+    foo_i
+}
+
+/// A function with attributes.
+#[portaldi::inject]
+#[deprecated]
+#[must_use]
+#[allow(clippy::let_and_return)]
+fn run_deprecated(foo_i: DI<dyn FooI>) -> DI<dyn FooI> {
+    let resolved = foo_i;
+    resolved
+}
+
+/// An async function.
+#[portaldi::inject]
+async fn run_async(
+    name: String,
+    #[inject(async)] bar: DI<AsyncBar>,
+    #[inject(FooProvider)] _foo: DI<Foo>,
+) -> (DI<AsyncBar>, String) {
+    (bar, name)
+}
+
+/// An async function, whose parameters are sync or async by their components.
+#[portaldi::inject]
+async fn run_inferred(bar: DI<AsyncBar>, _foo_i: DI<dyn FooI>) -> DI<AsyncBar> {
+    bar
+}
+
+/// An async function with multiple async parameters.
+#[portaldi::inject]
+async fn run_concurrently(
+    async_baz: DI<AsyncBaz>,
+    async_qux: DI<AsyncQux>,
+) -> (DI<AsyncBaz>, DI<AsyncQux>) {
+    (async_baz, async_qux)
+}
+
+mod foo {
+    use crate::common::*;
+
+    pub trait FooI: DITarget {}
+
+    #[derive(DIPortal)]
+    #[provide(FooI)]
+    pub struct Foo;
+    impl FooI for Foo {}
+
+    pub struct AsyncBar;
+
+    #[provider(Self)]
+    #[async_trait]
+    impl AsyncDIPortal for AsyncBar {
+        async fn create_for_di(_container: &DIContainer) -> Self {
+            AsyncBar
+        }
+    }
+
+    fn barrier() -> &'static tokio::sync::Barrier {
+        static BARRIER: std::sync::OnceLock<tokio::sync::Barrier> = std::sync::OnceLock::new();
+        BARRIER.get_or_init(|| tokio::sync::Barrier::new(2))
+    }
+
+    pub struct AsyncBaz;
+
+    #[provider(Self)]
+    #[async_trait]
+    impl AsyncDIPortal for AsyncBaz {
+        async fn create_for_di(_container: &DIContainer) -> Self {
+            barrier().wait().await;
+            AsyncBaz
+        }
+    }
+
+    pub struct AsyncQux;
+
+    #[provider(Self)]
+    #[async_trait]
+    impl AsyncDIPortal for AsyncQux {
+        async fn create_for_di(_container: &DIContainer) -> Self {
+            barrier().wait().await;
+            AsyncQux
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::common::*;
+
+fn original_global() -> DI<DIContainer> {
+    // the global container is replaced only on the test thread and for the test future.
+    std::thread::spawn(DIContainer::global).join().unwrap()
+}
+
+#[portaldi::testing::test]
+fn test_isolated() {
+    let hoge = Hoge::di();
+    assert!(ptr_eq(hoge.as_ref(), Hoge::di().as_ref()));
+    assert!(!DI::ptr_eq(&DIContainer::global(), &original_global()));
+    assert!(original_global().get::<Hoge>().is_none());
+}
+
+#[portaldi::testing::test]
+#[tokio::test]
+async fn test_isolated_async() {
+    let hoge = Hoge::di();
+    tokio::task::yield_now().await;
+    assert!(ptr_eq(hoge.as_ref(), Hoge::di().as_ref()));
+    assert!(original_global().get::<Hoge>().is_none());
+}
+
+#[tokio::test]
+#[portaldi::testing::test]
+async fn test_isolated_async_after_runtime() {
+    let hoge = Hoge::di();
+    tokio::task::yield_now().await;
+    assert!(ptr_eq(hoge.as_ref(), Hoge::di().as_ref()));
+    assert!(original_global().get::<Hoge>().is_none());
+}
+
+#[derive(DIPortal)]
+struct Hoge;
+
+#[portaldi::testing::test]
+#[tokio::test]
+async fn test_isolated_spawned() {
+    // tasks of the current thread runtime run on the test thread.
+    let hoge = tokio::spawn(async { Hoge::di() }).await.unwrap();
+    assert!(ptr_eq(hoge.as_ref(), Hoge::di().as_ref()));
+    assert!(original_global().get::<Hoge>().is_none());
+}
+
+#[derive(DIPortal)]
+struct Fuga;
+
+#[portaldi::testing::test]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_isolated_multi_thread() {
+    let fuga = Fuga::di();
+    tokio::task::yield_now().await;
+    // tasks on workers share the container by `replace_global_in`.
+    let spawned = tokio::spawn(replace_global_in(DIContainer::global(), async {
+        tokio::task::yield_now().await;
+        Fuga::di()
+    }))
+    .await
+    .unwrap();
+    assert!(ptr_eq(fuga.as_ref(), spawned.as_ref()));
+    assert!(original_global().get::<Fuga>().is_none());
+}
+
+#[derive(DIPortal)]
+struct Piyo;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[portaldi::testing::test]
+async fn test_isolated_multi_thread_after_runtime() {
+    let piyo = Piyo::di();
+    tokio::task::yield_now().await;
+    assert!(ptr_eq(piyo.as_ref(), Piyo::di().as_ref()));
+    assert!(original_global().get::<Piyo>().is_none());
+}
@@ -0,0 +1,127 @@
+use crate::common::*;
+
+use providers::*;
+use service::*;
+
+#[test]
+fn test_di() {
+    let hoge = Hoge::di();
+    assert!(ptr_eq(hoge.foo.as_ref(), FooIProvider::di().as_ref()));
+    assert!(ptr_eq(
+        hoge.bar.as_ref(),
+        BarI_GString_EProvider::di().as_ref()
+    ));
+    assert!(ptr_eq(hoge.baz.as_ref(), BazIProvider::di().as_ref()));
+    assert!(ptr_eq(hoge.piyo.as_ref(), PiyoProvider::di().as_ref()));
+}
+
+#[test]
+fn test_bindings() {
+    // this test crate is named `mod`, which is escaped only in `module_path!`.
+    let unescape = |p: &str| p.replace("r#", "");
+    let path = |p: &str| unescape(&format!("{}::{}", module_path!(), p));
+    let bindings: Vec<_> = providers::Module::bindings()
+        .into_iter()
+        .map(|b| (b.target, unescape(b.provider), unescape(b.module)))
+        .collect();
+    assert_eq!(unescape(providers::Module::name()), path("providers"));
+    assert_eq!(
+        bindings,
+        vec![
+            (
+                "dyn FooI",
+                path("foo_impl::FooIProvider"),
+                path("providers")
+            ),
+            (
+                "dyn BarI<String>",
+                path("bar_impl::BarI_GString_EProvider"),
+                path("providers")
+            ),
+            ("Piyo", path("PiyoProvider"), path("providers")),
+            (
+                "dyn BazI",
+                path("infra::baz_impl::BazIProvider"),
+                path("infra::providers")
+            ),
+        ]
+    );
+    assert_eq!(
+        providers::Module::binding_of::<dyn BazI>().map(|b| unescape(b.module)),
+        Some(path("infra::providers"))
+    );
+    assert_eq!(
+        providers::Module::binding_of::<dyn BarI<String>>().map(|b| b.target),
+        Some("dyn BarI<String>")
+    );
+    assert_eq!(providers::Module::binding_of::<dyn BarI<bool>>(), None);
+    assert_eq!(providers::Module::binding_of::<Hoge>(), None);
+}
+
+#[derive(DIPortal)]
+struct Hoge {
+    foo: DI<dyn FooI>,
+    bar: DI<dyn BarI<String>>,
+    baz: DI<dyn BazI>,
+    piyo: DI<Piyo>,
+}
+
+#[derive(DIPortal)]
+pub struct Piyo;
+
+portaldi::module! {
+    /// Providers for this test.
+    pub providers {
+        dyn FooI from foo_impl,
+        dyn BarI<String> from self::bar_impl,
+        Piyo,
+        include infra::providers,
+    }
+}
+
+mod service {
+    use crate::common::*;
+
+    pub trait FooI: DITarget {}
+    pub trait BarI<T>: DITarget {}
+    pub trait BazI: DITarget {}
+}
+
+// implementations are hidden in private modules.
+mod foo_impl {
+    use super::service::*;
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    #[provide(FooI)]
+    struct Foo;
+    impl FooI for Foo {}
+}
+
+mod bar_impl {
+    use super::service::*;
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    #[provide(BarI<String>)]
+    struct Bar;
+    impl BarI<String> for Bar {}
+}
+
+mod infra {
+    mod baz_impl {
+        use crate::common::*;
+        use crate::di_for_module::service::*;
+
+        #[derive(DIPortal)]
+        #[provide(BazI)]
+        struct Baz;
+        impl BazI for Baz {}
+    }
+
+    portaldi::module! {
+        pub providers {
+            dyn BazI from baz_impl,
+        }
+    }
+}
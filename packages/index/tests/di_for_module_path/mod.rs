@@ -0,0 +1,111 @@
+use crate::common::*;
+
+#[test]
+fn test_di() {
+    let app = di![crate::di_for_module_path::app::App];
+    assert!(ptr_eq(app.foo.as_ref(), impls::FooIProvider::di().as_ref()));
+    assert!(ptr_eq(
+        app.foo.as_ref(),
+        di![crate::di_for_module_path::service::FooI].as_ref()
+    ));
+    assert!(ptr_eq(
+        app.bar.as_ref(),
+        di![service::BarI<String>].as_ref()
+    ));
+    assert!(ptr_eq(
+        app.baz.as_ref(),
+        service::BazIProvider::di().as_ref()
+    ));
+    assert!(ptr_eq(app.qux.as_ref(), qux::Qux::di().as_ref()));
+    assert_eq!(app.bar.bar(), "bar");
+    assert_eq!(app.baz.baz(), "baz");
+}
+
+#[tokio::test]
+async fn test_async_di() {
+    let app = di![self::app::AsyncApp].await;
+    assert!(ptr_eq(
+        app.foo.as_ref(),
+        di![self::service::AsyncFooI].await.as_ref()
+    ));
+}
+
+mod service {
+    use crate::common::*;
+
+    pub trait FooI: DITarget {}
+    pub trait BarI<T>: DITarget {
+        fn bar(&self) -> T;
+    }
+    pub trait BazI: DITarget {
+        fn baz(&self) -> String;
+    }
+    pub trait AsyncFooI: DITarget {}
+
+    // default providers are named by the traits, and the others are re-exported.
+    pub type FooIProvider = ProviderOf<dyn FooI>;
+    pub type AsyncFooIProvider = ProviderOf<dyn AsyncFooI>;
+    pub use super::impls::{BarI_GString_EProvider, BazIProvider};
+}
+
+mod impls {
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    #[provide(crate::di_for_module_path::service::FooI, default)]
+    pub struct Foo;
+    impl crate::di_for_module_path::service::FooI for Foo {}
+
+    #[derive(DIPortal)]
+    #[provide(super::service::BarI<String>)]
+    pub struct Bar;
+    impl super::service::BarI<String> for Bar {
+        fn bar(&self) -> String {
+            "bar".to_string()
+        }
+    }
+
+    pub struct Baz;
+    impl super::service::BazI for Baz {
+        fn baz(&self) -> String {
+            "baz".to_string()
+        }
+    }
+    def_di_provider!(dyn super::service::BazI, |_| Baz);
+
+    pub struct AsyncFoo;
+    impl super::service::AsyncFooI for AsyncFoo {}
+
+    #[provider(super::service::AsyncFooI, default)]
+    #[async_trait]
+    impl AsyncDIPortal for AsyncFoo {
+        async fn create_for_di(_container: &DIContainer) -> Self {
+            AsyncFoo
+        }
+    }
+}
+
+mod qux {
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    pub struct Qux;
+}
+
+mod app {
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    pub struct App {
+        pub foo: DI<dyn crate::di_for_module_path::service::FooI>,
+        pub bar: DI<dyn super::service::BarI<String>>,
+        pub baz: DI<dyn super::service::BazI>,
+        pub qux: DI<super::qux::Qux>,
+    }
+
+    #[derive(DIPortal)]
+    pub struct AsyncApp {
+        #[inject(async)]
+        pub foo: DI<dyn super::service::AsyncFooI>,
+    }
+}
@@ -0,0 +1,171 @@
+use crate::common::*;
+
+#[test]
+fn test_resolve_named() {
+    let c = DIContainer::new();
+    // chosen at runtime, e.g. by a config file.
+    let s3 = c.resolve_named::<dyn StorageI>("storage.s3").unwrap();
+    assert_eq!(s3.name(), "s3");
+    assert!(ptr_eq(
+        s3.as_ref(),
+        c.resolve_named::<dyn StorageI>("storage.s3")
+            .unwrap()
+            .as_ref()
+    ));
+    assert!(ptr_eq(
+        s3.as_ref(),
+        s3::StorageIProvider::di_on(&c).as_ref()
+    ));
+    assert_eq!(
+        c.resolve_named::<dyn StorageI>("storage.local")
+            .unwrap()
+            .name(),
+        "local"
+    );
+    // a manual implementation with a name.
+    assert_eq!(
+        c.resolve_named::<dyn StorageI>("storage.memory")
+            .unwrap()
+            .name(),
+        "memory"
+    );
+
+    assert!(c.resolve_named::<dyn StorageI>("storage.gcs").is_none());
+    // a name is bound per target type.
+    assert!(c.resolve_named::<dyn CacheI>("storage.s3").is_none());
+}
+
+#[test]
+fn test_resolve_named_registered() {
+    let c = DIContainer::new();
+    c.register::<dyn StorageI>(|c| local::LocalStorage::di_on(c));
+    // a name chooses its implementation over registrations of the target type.
+    assert_eq!(
+        c.resolve_named::<dyn StorageI>("storage.s3")
+            .unwrap()
+            .name(),
+        "s3"
+    );
+    assert_eq!(s3::StorageIProvider::di_on(&c).name(), "local");
+}
+
+#[tokio::test]
+async fn test_resolve_named_async() {
+    let c = DIContainer::new();
+    let cache = c.resolve_named_async::<dyn CacheI>("cache.redis").await;
+    assert_eq!(cache.unwrap().name(), "redis");
+    // sync components are also resolved asynchronously.
+    let storage = c.resolve_named_async::<dyn StorageI>("storage.s3").await;
+    assert_eq!(storage.unwrap().name(), "s3");
+    assert!(c
+        .resolve_named_async::<dyn CacheI>("cache.memcached")
+        .await
+        .is_none());
+}
+
+#[test]
+#[should_panic(expected = "must be resolved by `resolve_named_async`")]
+fn test_resolve_named_async_resolved_sync() {
+    DIContainer::new().resolve_named::<dyn CacheI>("cache.redis");
+}
+
+#[test]
+fn test_names() {
+    let names = DIContainer::names()
+        .into_iter()
+        .filter(|b| b.name().starts_with("storage.") || b.name().starts_with("cache."))
+        .map(|b| (b.name(), b.is_async()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            ("cache.redis", true),
+            ("storage.local", false),
+            ("storage.memory", false),
+            ("storage.s3", false),
+        ]
+    );
+    let s3 = DIContainer::names()
+        .into_iter()
+        .find(|b| b.name() == "storage.s3")
+        .unwrap();
+    assert!(s3.target().ends_with("StorageI"));
+}
+
+pub trait StorageI: DITarget {
+    fn name(&self) -> &'static str;
+}
+
+pub trait CacheI: DITarget {
+    fn name(&self) -> &'static str;
+}
+
+// implementations of the same trait live in their own modules, where their providers are generated.
+mod local {
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    #[provide(super::StorageI, name = "storage.local")]
+    pub struct LocalStorage {}
+
+    impl super::StorageI for LocalStorage {
+        fn name(&self) -> &'static str {
+            "local"
+        }
+    }
+}
+
+mod s3 {
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    #[provide(super::StorageI, name = "storage.s3")]
+    pub struct S3Storage {}
+
+    impl super::StorageI for S3Storage {
+        fn name(&self) -> &'static str {
+            "s3"
+        }
+    }
+}
+
+mod memory {
+    use crate::common::*;
+
+    pub struct MemoryStorage {}
+
+    #[provider(super::StorageI, name = "storage.memory")]
+    impl DIPortal for MemoryStorage {
+        fn create_for_di(_container: &DIContainer) -> Self {
+            MemoryStorage {}
+        }
+    }
+
+    impl super::StorageI for MemoryStorage {
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+    }
+}
+
+#[derive(DIPortal)]
+#[provide(CacheI, name = "cache.redis")]
+struct RedisCache {
+    _client: DI<RedisClient>,
+}
+
+impl CacheI for RedisCache {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+}
+
+pub struct RedisClient {}
+
+#[provider(Self)]
+#[async_trait]
+impl AsyncDIPortal for RedisClient {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        RedisClient {}
+    }
+}
@@ -0,0 +1,117 @@
+use crate::common::*;
+use std::any::type_name;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[test]
+fn test_panic_in_creation() {
+    let c = DI::new(DIContainer::new());
+
+    BAR_FAILS.store(true, Ordering::SeqCst);
+    let payload = catch_unwind(AssertUnwindSafe(|| {
+        Hoge::di_on(&c);
+    }))
+    .unwrap_err();
+    // the original payload is raised again, and the chain is kept separately.
+    assert_eq!(payload.downcast_ref::<&str>().unwrap(), &"bar failed");
+    assert_eq!(
+        DIContainer::last_panic_chain().unwrap(),
+        vec![type_name::<Hoge>(), type_name::<Foo>(), type_name::<Bar>()]
+    );
+    // no component is half-registered.
+    assert!(c.get::<Hoge>().is_none());
+    assert!(c.get::<Foo>().is_none());
+    assert!(c.get::<Bar>().is_none());
+
+    // retry on the next resolution.
+    BAR_FAILS.store(false, Ordering::SeqCst);
+    let hoge = Hoge::di_on(&c);
+    assert!(ptr_eq(hoge.foo.bar.as_ref(), Bar::di_on(&c).as_ref()));
+
+    // the chain of the next panic is kept, too.
+    BAR_FAILS.store(true, Ordering::SeqCst);
+    catch_unwind(|| {
+        Bar::di_on(&DIContainer::new());
+    })
+    .unwrap_err();
+    BAR_FAILS.store(false, Ordering::SeqCst);
+    assert_eq!(
+        DIContainer::last_panic_chain().unwrap(),
+        vec![type_name::<Bar>()]
+    );
+}
+
+#[tokio::test]
+async fn test_panic_in_async_creation() {
+    let c = DI::new(DIContainer::new());
+
+    ASYNC_BAR_FAILS.store(true, Ordering::SeqCst);
+    let c2 = c.clone();
+    let err = tokio::spawn(async move {
+        AsyncHoge::di_on(&c2).await;
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.into_panic().downcast_ref::<&str>().unwrap(),
+        &"async bar failed"
+    );
+    // the task runs on the current thread of the test runtime.
+    assert_eq!(
+        DIContainer::last_panic_chain().unwrap(),
+        vec![type_name::<AsyncHoge>(), type_name::<AsyncBar>()]
+    );
+    assert!(c.get::<AsyncHoge>().is_none());
+    assert!(c.get::<AsyncBar>().is_none());
+
+    ASYNC_BAR_FAILS.store(false, Ordering::SeqCst);
+    let hoge = AsyncHoge::di_on(&c).await;
+    assert!(ptr_eq(
+        hoge.bar.as_ref(),
+        AsyncBar::di_on(&c).await.as_ref()
+    ));
+}
+
+static BAR_FAILS: AtomicBool = AtomicBool::new(false);
+static ASYNC_BAR_FAILS: AtomicBool = AtomicBool::new(false);
+
+#[derive(DIPortal)]
+struct Hoge {
+    foo: DI<Foo>,
+}
+
+#[derive(DIPortal)]
+struct Foo {
+    bar: DI<Bar>,
+}
+
+pub struct Bar {}
+
+#[provider(Self)]
+impl DIPortal for Bar {
+    fn create_for_di(_container: &DIContainer) -> Self {
+        if BAR_FAILS.load(Ordering::SeqCst) {
+            panic!("bar failed");
+        }
+        Bar {}
+    }
+}
+
+#[derive(DIPortal)]
+struct AsyncHoge {
+    bar: DI<AsyncBar>,
+}
+
+pub struct AsyncBar {}
+
+#[provider(Self)]
+#[async_trait]
+impl AsyncDIPortal for AsyncBar {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        tokio::task::yield_now().await;
+        if ASYNC_BAR_FAILS.load(Ordering::SeqCst) {
+            panic!("async bar failed");
+        }
+        AsyncBar {}
+    }
+}
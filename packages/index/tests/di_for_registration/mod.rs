@@ -0,0 +1,109 @@
+use crate::common::*;
+
+#[test]
+fn test_register() {
+    let c = DIContainer::new();
+    assert_eq!(StorageIProvider::di_on(&c).name(), "local");
+
+    // chosen at runtime, e.g. by a CLI flag.
+    let backend = "s3";
+    c.register::<dyn StorageI>(move |c| match backend {
+        "s3" => S3Storage::di_on(c),
+        _ => LocalStorage::di_on(c),
+    });
+    let storage = StorageIProvider::di_on(&c);
+    assert_eq!(storage.name(), "s3");
+    assert!(ptr_eq(storage.as_ref(), StorageIProvider::di_on(&c).as_ref()));
+
+    let scope = DI::new(c).scope();
+    assert_eq!(Uploader::di_on(&scope).storage.name(), "s3");
+}
+
+#[tokio::test]
+async fn test_register_async() {
+    let c = DIContainer::new();
+    assert_eq!(CacheIProvider::di_on(&c).await.name(), "memory");
+
+    c.register_async::<dyn CacheI>(|c| {
+        Box::pin(async move { RedisCache::di_on(c).await as DI<dyn CacheI> })
+    });
+    let service = Service::di_on(&c).await;
+    assert_eq!(service.cache.name(), "redis");
+    // compiled providers of other types are still used.
+    assert_eq!(service.storage.name(), "local");
+    assert!(ptr_eq(
+        service.cache.as_ref(),
+        CacheIProvider::di_on(&c).await.as_ref()
+    ));
+}
+
+#[derive(DIPortal)]
+struct Uploader {
+    storage: DI<dyn StorageI>,
+}
+
+#[derive(DIPortal)]
+struct Service {
+    storage: DI<dyn StorageI>,
+    cache: DI<dyn CacheI>,
+}
+
+pub trait StorageI: DITarget {
+    fn name(&self) -> &'static str;
+}
+
+#[derive(DIPortal)]
+#[provide(StorageI)]
+struct LocalStorage {}
+
+impl StorageI for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+}
+
+#[derive(DIPortal)]
+#[provide(none)]
+struct S3Storage {}
+
+impl StorageI for S3Storage {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+}
+
+pub trait CacheI: DITarget {
+    fn name(&self) -> &'static str;
+}
+
+pub struct MemoryCache {}
+
+#[provider(CacheI)]
+#[async_trait]
+impl AsyncDIPortal for MemoryCache {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        MemoryCache {}
+    }
+}
+
+impl CacheI for MemoryCache {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+}
+
+pub struct RedisCache {}
+
+#[provider(Self)]
+#[async_trait]
+impl AsyncDIPortal for RedisCache {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        RedisCache {}
+    }
+}
+
+impl CacheI for RedisCache {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+}
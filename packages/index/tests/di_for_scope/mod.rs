@@ -0,0 +1,58 @@
+use crate::common::*;
+
+#[test]
+fn test_di() {
+    let root = DI::new(DIContainer::new());
+    let scope1 = DI::new(root.scope());
+    let scope2 = DI::new(root.scope());
+
+    let hoge1 = Hoge::di_on(&scope1);
+    let hoge2 = Hoge::di_on(&scope2);
+    assert!(!ptr_eq(hoge1.as_ref(), hoge2.as_ref()));
+    assert!(ptr_eq(hoge1.as_ref(), Hoge::di_on(&scope1).as_ref()));
+    assert!(ptr_eq(hoge1.foo.as_ref(), hoge2.foo.as_ref()));
+    assert!(ptr_eq(hoge1.foo.as_ref(), Foo::di_on(&root).as_ref()));
+    assert!(ptr_eq(hoge1.bar.as_ref(), BarProvider::di_on(&root).as_ref()));
+}
+
+#[tokio::test]
+async fn test_async_di() {
+    let root = DI::new(DIContainer::new());
+    let scope1 = root.scope();
+    let scope2 = root.scope();
+
+    let hoge1 = AsyncHoge::di_on(&scope1).await;
+    let hoge2 = AsyncHoge::di_on(&scope2).await;
+    assert!(!ptr_eq(hoge1.as_ref(), hoge2.as_ref()));
+    assert!(ptr_eq(hoge1.foo.as_ref(), hoge2.foo.as_ref()));
+}
+
+#[derive(DIPortal)]
+#[scoped]
+struct Hoge {
+    foo: DI<Foo>,
+    bar: DI<Bar>,
+}
+
+#[derive(DIPortal)]
+#[scoped]
+struct AsyncHoge {
+    #[inject(async)]
+    foo: DI<AsyncFoo>,
+}
+
+#[derive(DIPortal)]
+struct Foo {}
+
+pub struct Bar {}
+def_di_provider!(Bar, |_| Bar {});
+
+pub struct AsyncFoo {}
+
+#[provider(Self)]
+#[async_trait]
+impl AsyncDIPortal for AsyncFoo {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        AsyncFoo {}
+    }
+}
@@ -10,10 +10,20 @@ async fn test_di() {
     di![Piyo2<String, ()> on c].await;
 }
 
+#[tokio::test]
+async fn test_di_for_multiple_traits() {
+    let store = Store::di().await;
+    let user_repo = UserRepoProvider::di().await;
+    let order_repo = OrderRepoProvider::di().await;
+    assert!(std::ptr::addr_eq(store.as_ref(), user_repo.as_ref()));
+    assert!(std::ptr::addr_eq(store.as_ref(), order_repo.as_ref()));
+}
+
 use bar::*;
 use baz::*;
 use foo::*;
 use piyo::*;
+use store::*;
 
 #[derive(DIPortal)]
 struct Hoge {
@@ -123,3 +133,22 @@ mod piyo {
 
     def_async_di_provider!(dyn Piyo2<String, ()>, |_| async { Piyo2Test {} });
 }
+
+mod store {
+    use super::*;
+
+    pub trait UserRepo: DITarget {}
+    pub trait OrderRepo: DITarget {}
+
+    pub struct Store {}
+    impl UserRepo for Store {}
+    impl OrderRepo for Store {}
+
+    #[portaldi::provider(UserRepo, OrderRepo)]
+    #[async_trait]
+    impl AsyncDIPortal for Store {
+        async fn create_for_di(_container: &DIContainer) -> Self {
+            Store {}
+        }
+    }
+}
@@ -1,2 +1,3 @@
 mod async_test;
+mod sibling_test;
 mod sync_test;
@@ -0,0 +1,73 @@
+use crate::common::*;
+
+#[test]
+fn test_di_by_imported_provider() {
+    let app = app::App::di();
+    let test_app = test_app::App::di();
+    assert_eq!(app.foo.name(), "prod");
+    assert_eq!(test_app.foo.name(), "mock");
+    assert!(ptr_eq(app.foo.as_ref(), prod::FooIProvider::di().as_ref()));
+    assert!(ptr_eq(
+        test_app.foo.as_ref(),
+        mock::FooIProvider::di().as_ref()
+    ));
+}
+
+mod service {
+    use crate::common::*;
+
+    pub trait FooI: DITarget {
+        fn name(&self) -> &str;
+    }
+}
+
+// providers of the same trait in sibling modules, which are chosen by importing.
+mod prod {
+    use super::service::FooI;
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    #[provide(FooI)]
+    pub struct Foo;
+    impl FooI for Foo {
+        fn name(&self) -> &str {
+            "prod"
+        }
+    }
+}
+
+mod mock {
+    use super::service::FooI;
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    #[provide(FooI)]
+    pub struct MockFoo;
+    impl FooI for MockFoo {
+        fn name(&self) -> &str {
+            "mock"
+        }
+    }
+}
+
+mod app {
+    use super::prod::FooIProvider;
+    use super::service::FooI;
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    pub struct App {
+        pub foo: DI<dyn FooI>,
+    }
+}
+
+mod test_app {
+    use super::mock::FooIProvider;
+    use super::service::FooI;
+    use crate::common::*;
+
+    #[derive(DIPortal)]
+    pub struct App {
+        pub foo: DI<dyn FooI>,
+    }
+}
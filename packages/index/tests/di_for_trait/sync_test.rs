@@ -6,17 +6,29 @@ fn test_di() {
     assert!(ptr_eq(hoge.foo1.as_ref(), hoge.foo2.as_ref()));
     assert!(!ptr_eq(hoge.bar1.as_ref(), hoge.bar2.as_ref()));
 
-    Piyo3StringUnitProvider::di();
+    Piyo3_GString_S_U_EProvider::di();
 
     di![Piyo3<String, ()>];
     let c = &DIContainer::new();
     di![Piyo3<String, ()> on c];
 }
 
+#[test]
+fn test_di_for_multiple_traits() {
+    let store = Store::di();
+    let user_repo = UserRepoProvider::di();
+    let order_repo = OrderRepoProvider::di();
+    let audit_log = AuditLogProvider::di();
+    assert!(std::ptr::addr_eq(store.as_ref(), user_repo.as_ref()));
+    assert!(std::ptr::addr_eq(store.as_ref(), order_repo.as_ref()));
+    assert!(std::ptr::addr_eq(store.as_ref(), audit_log.as_ref()));
+}
+
 use bar::*;
 use baz::*;
 use foo::*;
 use piyo::*;
+use store::*;
 
 #[derive(DIPortal)]
 struct Hoge {
@@ -41,6 +53,8 @@ struct Hoge {
     _piyo3: DI<dyn Piyo3<String, bool>>,
     // di for a trait with generics that contains ()
     _piyo3_unit: DI<dyn Piyo3<String, ()>>,
+    // di for a trait with nested generics, tuples and references
+    _piyo_nested: DI<dyn Piyo<Vec<u8>, (String, &'static str)>>,
 }
 
 mod foo {
@@ -95,6 +109,9 @@ mod piyo {
 
     def_di_provider!(dyn Piyo<String, bool>, |_c| PiyoTest {});
 
+    impl Piyo<Vec<u8>, (String, &'static str)> for PiyoTest {}
+    def_di_provider!(dyn Piyo<Vec<u8>, (String, &'static str)>, |_c| PiyoTest {});
+
     //
 
     pub trait Piyo2<A, B>: DITarget {}
@@ -121,3 +138,19 @@ mod piyo {
 
     def_di_provider!(dyn Piyo3<String, ()>, |_| { Piyo3Test {} });
 }
+
+mod store {
+    use super::*;
+
+    pub trait UserRepo: DITarget {}
+    pub trait OrderRepo: DITarget {}
+    pub trait AuditLog: DITarget {}
+
+    #[derive(DIPortal)]
+    #[provide(UserRepo, OrderRepo)]
+    #[provide(AuditLog)]
+    pub struct Store {}
+    impl UserRepo for Store {}
+    impl OrderRepo for Store {}
+    impl AuditLog for Store {}
+}
@@ -0,0 +1,65 @@
+use crate::common::*;
+
+#[test]
+fn test_di() {
+    let hoge = Hoge::di();
+    assert!(ptr_eq(hoge.0.as_ref(), Foo::di().as_ref()));
+    assert!(ptr_eq(hoge.1.as_ref(), BarIProvider::di().as_ref()));
+    assert!(ptr_eq(hoge.1.foo().as_ref(), Foo::di().as_ref()));
+
+    let wrapper = BarIWrapper::di();
+    assert!(ptr_eq(wrapper.0.as_ref(), BarIProvider::di().as_ref()));
+
+    Unit::di();
+}
+
+#[tokio::test]
+async fn test_async_di() {
+    let hoge = AsyncHoge::di().await;
+    assert!(ptr_eq(hoge.0.as_ref(), Foo::di().as_ref()));
+    assert!(ptr_eq(hoge.1.as_ref(), AsyncFoo::di().await.as_ref()));
+    assert!(ptr_eq(hoge.2.as_ref(), Foo::di().as_ref()));
+}
+
+#[derive(DIPortal)]
+struct Hoge(DI<Foo>, DI<dyn BarI>);
+
+// newtype that wraps a trait object
+#[derive(DIPortal)]
+struct BarIWrapper(DI<dyn BarI>);
+
+#[derive(DIPortal)]
+struct Unit;
+
+#[derive(DIPortal)]
+struct AsyncHoge(
+    DI<Foo>,
+    #[inject(async)] DI<AsyncFoo>,
+    #[inject(FooProvider)] DI<Foo>,
+);
+
+#[derive(DIPortal)]
+pub struct Foo {}
+
+pub trait BarI: DITarget {
+    fn foo(&self) -> &DI<Foo>;
+}
+
+#[derive(DIPortal)]
+#[provide(BarI)]
+struct Bar(#[inject(Foo)] DI<Foo>);
+impl BarI for Bar {
+    fn foo(&self) -> &DI<Foo> {
+        &self.0
+    }
+}
+
+pub struct AsyncFoo;
+
+#[provider(Self)]
+#[async_trait]
+impl AsyncDIPortal for AsyncFoo {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        AsyncFoo
+    }
+}
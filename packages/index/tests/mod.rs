@@ -1,5 +1,18 @@
 mod bugfix;
 mod common;
+mod compile_fail;
+mod di_for_async_inference;
+mod di_for_binding;
+mod di_for_builder;
 mod di_for_complex_component_creation;
 mod di_for_concrete_type;
+mod di_for_function;
+mod di_for_isolated_test;
+mod di_for_module;
+mod di_for_module_path;
+mod di_for_named;
+mod di_for_panic;
+mod di_for_registration;
+mod di_for_scope;
 mod di_for_trait;
+mod di_for_tuple_struct;
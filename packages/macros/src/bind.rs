@@ -0,0 +1,180 @@
+macro_rules! define {
+    () => {
+        /// Generate a [`DIProvider`] or [`AsyncDIProvider`] implementation that binds a target type to an implementation.
+        ///
+        /// The implementation is resolved through its provider (`FooProvider` for `Foo`),
+        /// so it can be any type that has a provider, including a type of another crate
+        /// whose provider is defined by [`def_di_provider!`]. The binding is sync or async by the implementation.
+        /// An untagged binding also implements `IntoTarget`, so that the implementation can be bound by `DIContainerBuilder::bind`.
+        ///
+        /// ```ignore
+        /// bind!(dyn FooI => Foo);                  // FooIProvider will be generated.
+        /// bind!(dyn FooI<A> => crate::infra::Foo); // FooI_GA_EProvider will be generated.
+        /// bind!(dyn BarI => Bar);                  // AsyncDIProvider if Bar is created asynchronously.
+        /// bind!(dyn BarI => async Bar);            // through AsyncDIProvider of Bar explicitly.
+        ///
+        /// // Tagged<dyn FooI, Primary> can be injected.
+        /// bind!(dyn FooI => Foo, tag = Primary);
+        /// ```
+        #[proc_macro]
+        pub fn bind(input: TokenStream) -> TokenStream {
+            bind::exec(input.into()).into()
+        }
+    };
+}
+pub(crate) use define;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse2, parse_quote,
+    spanned::Spanned,
+    Token, Type,
+};
+
+use crate::helper::{
+    build_provider_resolver_exprs, build_resolver, config, kw, provider_ident, ProvideTarget,
+    ResolverExprs,
+};
+
+pub fn exec(input: TokenStream2) -> TokenStream2 {
+    expand(input).unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn expand(input: TokenStream2) -> syn::Result<TokenStream2> {
+    let BindInput {
+        kw_dyn,
+        target,
+        kw_async,
+        impl_target,
+        tag,
+    } = parse2(input)?;
+
+    let impl_provider = impl_target.provider_path()?;
+    let target_type = quote!(#kw_dyn #target);
+    // the mode of the implementation is inferred, unless `async` is specified for a hand-written provider.
+    let (mode_type, resolve_sync, resolve_async) = if kw_async.is_some() {
+        (
+            quote!(portaldi::AsyncMode),
+            None,
+            quote!(<#impl_provider as portaldi::AsyncDIProvider>::di_on(container).await),
+        )
+    } else {
+        let ResolverExprs {
+            sync_expr,
+            async_expr,
+            is_async_expr,
+        } = build_provider_resolver_exprs(&impl_provider, impl_target.span());
+        (
+            quote!(portaldi::ResolveMode<{ #is_async_expr }>),
+            Some(sync_expr),
+            quote!(#async_expr.await),
+        )
+    };
+
+    let mut into_target_quote = quote!();
+    let (provider, output, sync_body, async_body) = match tag {
+        None => {
+            let provider = target.provider_ident()?;
+            if kw_dyn.is_some() {
+                // so that the implementation can be bound by `DIContainerBuilder::bind`, too.
+                into_target_quote = quote! {
+                    impl portaldi::IntoTarget<#target_type> for #impl_target {
+                        fn into_target(self: portaldi::DI<Self>) -> portaldi::DI<#target_type> {
+                            self
+                        }
+                    }
+                };
+            }
+            (provider, target_type, resolve_sync, resolve_async)
+        }
+        Some(tag) => {
+            let provider = provider_ident(
+                &format_ident!("Tagged", span = target.path.segments[0].ident.span()),
+                &parse_quote!(#target_type, #tag),
+            )?;
+            // a tagged component is a singleton in the root container.
+            let sync_body = resolve_sync.map(|resolve| {
+                quote! {
+                    let container = container.root();
+                    container.get_or_init(|| {
+                        let target: portaldi::DI<#target_type> = #resolve;
+                        portaldi::Tagged::wrap(target)
+                    })
+                }
+            });
+            let async_body = quote! {
+                if let Some(token) = portaldi::SyncToken::of() {
+                    return <Self as portaldi::DIResolver>::resolve_sync(container, token);
+                }
+                let container = container.root();
+                container.get_or_init_async(|| async {
+                    let target: portaldi::DI<#target_type> = #resolve_async;
+                    portaldi::Tagged::wrap(target)
+                }).await
+            };
+            (
+                provider,
+                quote!(portaldi::Tagged<#target_type, #tag>),
+                sync_body,
+                async_body,
+            )
+        }
+    };
+
+    let resolver_quote = build_resolver(
+        &quote!(#provider),
+        &output,
+        &mode_type,
+        sync_body,
+        async_body,
+    );
+
+    let config = config()?;
+    let vis = config.default_visibility();
+    let tracking_quote = config.tracking();
+
+    Ok(quote! {
+        #[allow(non_camel_case_types)]
+        #vis struct #provider;
+
+        #resolver_quote
+        #into_target_quote
+        #tracking_quote
+    })
+}
+
+struct BindInput {
+    kw_dyn: Option<Token![dyn]>,
+    target: ProvideTarget,
+    kw_async: Option<Token![async]>,
+    impl_target: ProvideTarget,
+    tag: Option<Type>,
+}
+
+impl Parse for BindInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let kw_dyn = input.parse()?;
+        let target = input.parse()?;
+        let _: Token![=>] = input.parse()?;
+        let kw_async = input.parse()?;
+        let impl_target = input.parse()?;
+        let tag = if input.peek(Token![,]) && input.peek2(kw::tag) {
+            let _: Token![,] = input.parse()?;
+            let _: kw::tag = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let _: Option<Token![,]> = input.parse()?;
+        Ok(Self {
+            kw_dyn,
+            target,
+            kw_async,
+            impl_target,
+            tag,
+        })
+    }
+}
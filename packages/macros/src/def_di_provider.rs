@@ -20,6 +20,12 @@ macro_rules! define {
         ///     // some creation logic
         /// });
         ///
+        /// // A trait in another module can be specified by its path.
+        /// // HogeIProvider will be generated in the current module.
+        /// def_di_provider!(dyn crate::service::HogeI, |c| {
+        ///     // some creation logic
+        /// });
+        ///
         /// ```
         #[proc_macro]
         pub fn def_di_provider(input: TokenStream) -> TokenStream {
@@ -30,31 +36,44 @@ macro_rules! define {
 pub(crate) use define;
 
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
+use quote::quote;
 use syn::parse2;
 
-use crate::helper::DefDiProviderInput;
+use crate::helper::{build_resolver, config, DefDiProviderInput};
 
 pub fn exec(input: TokenStream2) -> TokenStream2 {
+    expand(input).unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn expand(input: TokenStream2) -> syn::Result<TokenStream2> {
     let DefDiProviderInput {
         kw_dyn,
-        target_ident,
-        generics,
+        target,
         create_fn,
         ..
-    } = parse2::<DefDiProviderInput>(input).unwrap();
+    } = parse2::<DefDiProviderInput>(input)?;
 
-    let ty_params_str = generics.type_params_str();
-    let provider_ident = format_ident!("{}{}Provider", target_ident, ty_params_str);
+    let provider_ident = target.provider_ident()?;
+    let resolver_quote = build_resolver(
+        &quote!(#provider_ident),
+        &quote!(#kw_dyn #target),
+        &quote!(portaldi::SyncMode),
+        Some(quote! {
+            let c = container.root();
+            c.get_or_init(|| (#create_fn)(c))
+        }),
+        quote!(<Self as portaldi::DIProvider>::di_on(container)),
+    );
 
-    quote! {
-        pub struct #provider_ident;
-        impl portaldi::DIProvider for #provider_ident {
-            type Output = #kw_dyn #target_ident #generics;
+    let config = config()?;
+    let vis = config.default_visibility();
+    let tracking_quote = config.tracking();
 
-            fn di_on(c: &portaldi::DIContainer) -> portaldi::DI<Self::Output> {
-                c.get_or_init(|| (#create_fn)(c))
-            }
-        }
-    }
+    Ok(quote! {
+        #[allow(non_camel_case_types)]
+        #vis struct #provider_ident;
+
+        #resolver_quote
+        #tracking_quote
+    })
 }
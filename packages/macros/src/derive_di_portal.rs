@@ -10,15 +10,53 @@ macro_rules! define {
         ///     foo: DI<dyn FooI>  // needs FooIProvider in the current scope.
         ///   }
         ///   ```
+        ///   A provider out of scope is reported as an unresolved type, e.g. ``cannot find type `FooIProvider` ``.
+        ///   Generate it by `#[provide(FooI)]` on an implementation, or import it.
         ///   For a trait with generics,
         ///   ```ignore
         ///   #[derive(DIPortal)]
-        ///   #[provide(HogeI<A>)] // HogeIAProvider will be generated.
+        ///   #[provide(HogeI<A>)] // HogeI_GA_EProvider will be generated.
         ///   struct Hoge {
-        ///     foo: DI<dyn FooI<B>>  // needs FooIBProvider in the current scope.
+        ///     foo: DI<dyn FooI<B>>  // needs FooI_GB_EProvider in the current scope.
         ///   }
         ///   ```
         ///
+        ///   For multiple traits, all providers share the same `Hoge` instance.
+        ///   ```ignore
+        ///   #[derive(DIPortal)]
+        ///   #[provide(HogeI, PiyoI)] // HogeIProvider and PiyoIProvider will be generated.
+        ///   #[provide(FugaI)]        // FugaIProvider will be generated.
+        ///   struct Hoge {}
+        ///   ```
+        ///
+        ///   A `name` registers named bindings of the providers, which are resolved by `DIContainer::resolve_named`.
+        ///   ```ignore
+        ///   #[derive(DIPortal)]
+        ///   #[provide(StorageI, name = "storage.s3")]
+        ///   struct S3Storage {}
+        ///
+        ///   container.resolve_named::<dyn StorageI>("storage.s3");
+        ///   ```
+        ///
+        ///   `default` registers the provider as the default one of the trait,
+        ///   so that the module of the trait can name it without importing the implementation.
+        ///   ```ignore
+        ///   #[derive(DIPortal)]
+        ///   #[provide(crate::service::FooI, default)]
+        ///   struct Foo {}
+        ///
+        ///   // in crate::service
+        ///   pub type FooIProvider = portaldi::ProviderOf<dyn FooI>;
+        ///   ```
+        ///
+        ///   Without `provide`, providers are generated by `provider-patterns` of the configuration.
+        ///   `#[provide(none)]` opts out of them.
+        ///   ```ignore
+        ///   #[derive(DIPortal)]
+        ///   #[provide(none)] // FooIProvider will not be generated by `^(\w+)Impl$`.
+        ///   struct FooIImpl {}
+        ///   ```
+        ///
         /// * `inject`: specify DI settings for a field.
         ///   ```ignore
         ///   #[derive(DIPortal)]
@@ -28,19 +66,49 @@ macro_rules! define {
         ///     #[inject(AsyncFoo, async)]  // specify concrete type that needs async creation,
         ///                                 // and consequently AsyncDIPortal for Hoge will be generated.
         ///     foo2: DI<dyn FooI>,
-        ///     #[inject(async)]  // Bar nedds async creation,
-        ///                       // and consequently AsyncDIPortal for Hoge will be generated.
+        ///     bar: DI<Bar>,     // if Bar needs async creation, AsyncDIPortal for Hoge will be generated.
         ///                       // implicitly BarProvider is used.
-        ///     bar: DI<Bar>,
         ///     #[inject(MyBazProvider)] // specify DI provider for a another crate concrete type.
         ///     baz: DI<Baz>,
-        ///     baz2: DI<Baz>,            // implicitly BarProvider is used.
+        ///     baz2: DI<Baz>,            // implicitly BazProvider is used.
         ///     piyo: DI<dyn IPiyo>,      // implicitly IPiyoProvider is used.
-        ///     piyo2: DI<dyn IPiyo2<A>>, // implicitly IPiyo2AProvider is used.
+        ///     piyo2: DI<dyn IPiyo2<A>>, // implicitly IPiyo2_GA_EProvider is used.
         ///   }
         ///   ```
         ///
-        #[proc_macro_derive(DIPortal, attributes(provide, inject))]
+        /// * `scoped`: create a component per scope (e.g. per request), instead of a singleton.
+        ///   ```ignore
+        ///   #[derive(DIPortal)]
+        ///   #[scoped]
+        ///   struct RequestContext {}
+        ///
+        ///   let scope = DIContainer::global().scope();
+        ///   RequestContext::di_on(&scope);
+        ///   ```
+        ///   When `default-lifetime = "scoped"` is configured, `singleton` makes a component a singleton.
+        ///
+        /// * Tuple structs and newtypes are also supported.
+        ///   ```ignore
+        ///   #[derive(DIPortal)]
+        ///   struct Hoge(DI<Foo>, DI<dyn BarI>);
+        ///
+        ///   #[derive(DIPortal)]
+        ///   #[provide(HogeI)]
+        ///   struct HogeWrapper(DI<dyn FooI>);
+        ///   ```
+        ///
+        /// * Types in other modules can be specified by their paths.
+        ///   Providers are looked up next to the type or the trait.
+        ///   ```ignore
+        ///   #[derive(DIPortal)]
+        ///   #[provide(crate::service::HogeI)] // HogeIProvider will be generated here.
+        ///   struct Hoge {
+        ///     foo: DI<dyn crate::service::FooI>, // crate::service::FooIProvider is used.
+        ///     bar: DI<crate::app::Bar>,          // crate::app::BarProvider is used.
+        ///   }
+        ///   ```
+        ///
+        #[proc_macro_derive(DIPortal, attributes(provide, inject, scoped, singleton))]
         pub fn derive_di_portal(input: TokenStream) -> TokenStream {
             derive_di_portal::exec(input.into()).into()
         }
@@ -50,23 +118,22 @@ pub(crate) use define;
 
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use syn::{
-    parse::{Parse, ParseStream},
-    parse2,
-    punctuated::Punctuated,
-    Attribute, Data, DeriveInput, GenericArgument, Ident, Meta, Path, PathArguments, Token, Type,
-    TypeParamBound, TypeTuple,
-};
+use syn::{parse2, parse_quote, Attribute, Data, DeriveInput, Fields, Ident, Index, Member, Meta};
 
 use crate::helper::{
-    async_trait_attr, attr_of, build_provider, build_provider_by_env, Generics_, ProvideTarget,
+    async_trait_attr, attr_of, attrs_of, build_di_expr, build_named_trait_providers,
+    build_provider, build_provider_by_pattern, build_resolver_exprs, build_static_singleton,
+    config, kw, parse_inject_attr, Generics_, InjectAttr, Lifetime, ProvideTarget, ProvideTargets,
+    ResolveMode, ResolverExprs,
 };
 
 pub fn exec(input: TokenStream) -> TokenStream {
-    let is_always_async = std::env::var("PORTALDI_ALWAYS_ASYNC")
-        .ok()
-        .and_then(|s| s.parse::<bool>().ok())
-        .unwrap_or(false);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let config = config()?;
+    let is_always_async = config.always_async;
 
     let DeriveInput {
         data,
@@ -75,288 +142,280 @@ pub fn exec(input: TokenStream) -> TokenStream {
         generics,
         attrs,
         ..
-    } = parse2(input).unwrap();
-
-    match data {
-        Data::Struct(s) => {
-            let field_dis: Vec<_> = s
-                .fields
-                .iter()
-                .map(|f| {
-                    let inject_attr = parse_inject_attr(&f.attrs);
-                    let is_async = is_always_async
-                        || inject_attr.as_ref().map(|a| a.is_async).unwrap_or(false);
-                    let inject_path = inject_attr.as_ref().and_then(|a| a.path.as_ref());
-                    let di_expr = build_field_di(&f, inject_path);
-                    let field_ident = f.ident.as_ref().unwrap().clone();
-                    FieldDI {
-                        field_ident,
-                        is_async,
-                        di_expr,
-                    }
-                })
-                .collect();
-
-            let is_totally_async = is_always_async || field_dis.iter().any(|f| f.is_async);
-            let di_portal_quote = build_portal(&ident, field_dis, is_totally_async);
-
-            let provider_quote = if let Some(provide_attr) = attr_of(&attrs, "provide") {
-                let provide_target = provide_attr.parse_args::<ProvideTarget>().unwrap();
-                build_provider(&ident, &provide_target, is_totally_async, true, None)
-            } else {
-                build_provider_by_env(&ident, is_totally_async)
-            };
+    } = parse2(input)?;
 
-            let self_provider_quote = build_provider(
+    let s = match data {
+        Data::Struct(s) => s,
+        _ => {
+            return Err(syn::Error::new_spanned(
                 &ident,
-                &ProvideTarget {
-                    ident: ident.clone(),
-                    generics: Generics_ {
-                        lt: generics.lt_token,
-                        params: generics
-                            .params
-                            .iter()
-                            .map(|p| syn::parse2::<Type>(quote!(#p)).unwrap())
-                            .collect(),
-                        gt: generics.gt_token,
-                    },
-                },
-                is_totally_async,
-                false,
-                Some(&vis),
-            );
+                "#[derive(DIPortal)] must be on a struct type",
+            ))
+        }
+    };
+    if !generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &generics,
+            "#[derive(DIPortal)] does not support generic structs; \
+             implement DIPortal manually and annotate it with #[provider(Self)]",
+        ));
+    }
 
-            let result = quote! {
-                #provider_quote
-                #self_provider_quote
-                #di_portal_quote
+    let field_dis = s
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let inject_attr = parse_inject_attr(&f.attrs)?;
+            let resolve = match &inject_attr {
+                // the mode is inferred from the provider.
+                None => FieldResolve::Auto(build_resolver_exprs(&f.ty)?),
+                Some(InjectAttr { path, is_async }) => {
+                    let is_async = is_always_async || *is_async;
+                    let di_expr = build_di_expr(&f.ty, path.as_ref(), is_async)?;
+                    if is_async {
+                        FieldResolve::Async(di_expr)
+                    } else {
+                        FieldResolve::Sync(di_expr)
+                    }
+                }
             };
+            let member = f
+                .ident
+                .clone()
+                .map(Member::Named)
+                .unwrap_or_else(|| Member::Unnamed(Index::from(i)));
+            Ok(FieldDI { member, resolve })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
 
-            // println!("check !!!! {:}", result);
-
-            result.into()
+    let is_scoped = match (attr_of(&attrs, "scoped"), attr_of(&attrs, "singleton")) {
+        (Some(_), None) => true,
+        (None, Some(_)) => false,
+        (None, None) => config.default_lifetime == Lifetime::Scoped,
+        (Some(_), Some(attr)) => {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "#[scoped] and #[singleton] are exclusive",
+            ))
         }
-        _ => syn::Error::new_spanned(&ident, "Must be struct type")
-            .to_compile_error()
-            .into(),
-    }
-}
+    };
+    let di_portal_quote = build_portal(&ident, &s.fields, field_dis, is_always_async, is_scoped);
 
-struct InjectAttr {
-    path: Option<Path>,
-    is_async: bool,
-}
+    let provide_attrs = attrs_of(&attrs, "provide")
+        .map(parse_provide_attr)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let provider_quote = match provide_attrs.as_slice() {
+        [] => build_provider_by_pattern(&ident, &Generics_::default(), ResolveMode::Auto)?,
+        [ProvideAttr::None(_)] => quote! {},
+        _ => {
+            let mut provide_targets = Vec::new();
+            for provide_attr in &provide_attrs {
+                match provide_attr {
+                    ProvideAttr::Targets(targets) => provide_targets.push(targets),
+                    ProvideAttr::None(none) => {
+                        return Err(syn::Error::new_spanned(
+                            none,
+                            "#[provide(none)] can't be combined with other provide targets",
+                        ))
+                    }
+                }
+            }
+            build_named_trait_providers(
+                &ident,
+                &Generics_::default(),
+                provide_targets,
+                ResolveMode::Auto,
+            )?
+        }
+    };
 
-fn parse_inject_attr(attrs: &Vec<Attribute>) -> Option<InjectAttr> {
-    attr_of(attrs, "inject").and_then(|attr| match &attr.meta {
-        Meta::List(metas) => {
-            let args = metas
-                .parse_args_with(Punctuated::<InjectAttrPart, Token![,]>::parse_terminated)
-                .unwrap();
+    let self_provider_quote = build_provider(
+        &ident,
+        &ProvideTarget::new(ident.clone(), Generics_::default()),
+        ResolveMode::Auto,
+        false,
+        Some(&vis),
+    )?;
 
-            let is_async = args.iter().any(|arg| arg == &InjectAttrPart::Async);
-            let path = args.iter().find_map(|arg| match arg {
-                InjectAttrPart::Path(p) => Some(p.clone()),
-                _ => None,
-            });
+    let tracking_quote = config.tracking();
 
-            Some(InjectAttr { path, is_async })
-        }
-        _ => None,
+    Ok(quote! {
+        #provider_quote
+        #self_provider_quote
+        #di_portal_quote
+        #tracking_quote
     })
 }
 
-fn build_field_di(f: &syn::Field, inject_path: Option<&Path>) -> proc_macro2::TokenStream {
-    inject_path.map_or_else(
-        || {
-            let DIType {
-                type_ident: di_type,
-                type_params,
-            } = get_di_type(&f.ty)
-                .expect(format!("{:?} is not DI type", &f.ident.as_ref()).as_str());
+enum ProvideAttr {
+    /// `#[provide(none)]`, which opts out of provider patterns.
+    None(kw::none),
+    Targets(ProvideTargets),
+}
 
-            let type_params_str = type_params
-                .iter()
-                .map(|p| p.to_string())
-                .collect::<Vec<_>>()
-                .concat();
-            let di_provider_type = quote::format_ident!("{}{}Provider", di_type, type_params_str);
-            quote! {
-                #di_provider_type::di_on(container)
+fn parse_provide_attr(attr: &Attribute) -> syn::Result<ProvideAttr> {
+    match &attr.meta {
+        Meta::List(list) if !list.tokens.is_empty() => {
+            if let Ok(none) = parse2::<kw::none>(list.tokens.clone()) {
+                return Ok(ProvideAttr::None(none));
             }
-        },
-        |path| {
-            quote! {
-                #path::di_on(container)
-            }
-        },
-    )
+            list.parse_args().map(ProvideAttr::Targets)
+        }
+        _ => Err(syn::Error::new_spanned(
+            attr,
+            "expected a trait to provide, e.g. #[provide(FooI)]",
+        )),
+    }
 }
 
-struct DIType<'a> {
-    type_ident: &'a Ident,
-    type_params: Vec<Ident>,
+struct FieldDI {
+    member: Member,
+    resolve: FieldResolve,
 }
 
-fn get_di_type(ty: &Type) -> Option<DIType<'_>> {
-    if let Type::Path(x) = ty {
-        let last_path_segment = x.path.segments.last().unwrap();
+enum FieldResolve {
+    /// `#[inject(..)]` without `async`.
+    Sync(TokenStream),
+    /// `#[inject(async)]` or `#[inject(.., async)]`.
+    Async(TokenStream),
+    /// No `#[inject]`, resolved through `DIResolver` of the implicit provider.
+    Auto(ResolverExprs),
+}
 
-        if last_path_segment.ident != "DI" {
-            return None;
+impl FieldDI {
+    fn var_name(&self) -> Ident {
+        match &self.member {
+            Member::Named(ident) => format_ident!("__di{}", ident),
+            Member::Unnamed(index) => format_ident!("__di{}", index.index),
         }
+    }
 
-        if let PathArguments::AngleBracketed(x) = &last_path_segment.arguments {
-            let path = match x.args.first().unwrap() {
-                GenericArgument::Type(Type::TraitObject(x)) => {
-                    if let TypeParamBound::Trait(x) = x.bounds.first().unwrap() {
-                        Some(&x.path)
-                    } else {
-                        None
-                    }
-                }
-                GenericArgument::Type(Type::Path(x)) => Some(&x.path),
-                _ => None,
-            };
-            if let Some(path) = path {
-                let last_seg = path.segments.last().unwrap();
-                let type_params = match &last_seg.arguments {
-                    PathArguments::AngleBracketed(x) => x
-                        .args
-                        .iter()
-                        .flat_map(|arg| match arg {
-                            GenericArgument::Type(Type::Path(p)) => {
-                                p.path.segments.last().map(|s| s.ident.clone())
-                            }
-                            GenericArgument::Type(Type::Tuple(TypeTuple { elems, .. }))
-                                if elems.is_empty() =>
-                            {
-                                Some(syn::parse2::<Ident>(quote!(Unit)).unwrap())
-                            }
-                            _ => None,
-                        })
-                        .collect(),
-                    _ => vec![],
-                };
+    /// Expression for synchronous creation, or `None` if the field is always async.
+    fn sync_expr(&self) -> Option<&TokenStream> {
+        match &self.resolve {
+            FieldResolve::Sync(expr) => Some(expr),
+            FieldResolve::Async(_) => None,
+            FieldResolve::Auto(exprs) => Some(&exprs.sync_expr),
+        }
+    }
 
-                return Some(DIType {
-                    type_ident: &last_seg.ident,
-                    type_params,
-                });
-            }
+    /// Expression for asynchronous creation, and whether it needs `.await`.
+    fn async_expr(&self) -> (&TokenStream, bool) {
+        match &self.resolve {
+            FieldResolve::Sync(expr) => (expr, false),
+            FieldResolve::Async(expr) => (expr, true),
+            FieldResolve::Auto(exprs) => (&exprs.async_expr, true),
         }
     }
-    return None;
 }
 
-struct FieldDI {
-    field_ident: syn::Ident,
-    is_async: bool,
-    di_expr: proc_macro2::TokenStream,
+/// Mode of a struct: async if any field is async.
+fn build_mode_type(field_dis: &[FieldDI], is_always_async: bool) -> TokenStream {
+    let mut is_async_exprs = vec![];
+    for f in field_dis {
+        match &f.resolve {
+            FieldResolve::Sync(_) => {}
+            FieldResolve::Async(_) => return quote!(portaldi::AsyncMode),
+            FieldResolve::Auto(exprs) => is_async_exprs.push(&exprs.is_async_expr),
+        }
+    }
+    if is_always_async {
+        quote!(portaldi::AsyncMode)
+    } else if is_async_exprs.is_empty() {
+        quote!(portaldi::SyncMode)
+    } else {
+        // through a const, so that the mode doesn't expose field types in a public provider.
+        quote!(portaldi::ResolveMode<{ #((#is_async_exprs))||* }>)
+    }
 }
 
 fn build_portal(
     ident: &Ident,
+    fields: &Fields,
     field_dis: Vec<FieldDI>,
-    is_totally_async: bool,
-) -> proc_macro2::TokenStream {
-    let to_var_name = |s: &syn::Ident| format_ident!("__di{}", &s);
-    let di_var_quotes = if cfg!(feature = "futures-join") {
-        let (async_field_dis, sync_field_dis): (Vec<_>, Vec<_>) =
-            field_dis.iter().partition(|f| f.is_async);
+    is_always_async: bool,
+    is_scoped: bool,
+) -> TokenStream {
+    let var_names = field_dis.iter().map(|f| f.var_name()).collect::<Vec<_>>();
+    let construct_quote = match fields {
+        Fields::Named(_) => {
+            let members = field_dis.iter().map(|f| &f.member);
+            quote! { #ident { #(#members: #var_names),* } }
+        }
+        Fields::Unnamed(_) => quote! { #ident(#(#var_names),*) },
+        Fields::Unit => quote! { #ident },
+    };
 
-        let async_di_exprs = async_field_dis.iter().map(|f| &f.di_expr);
-        let async_var_names = async_field_dis.iter().map(|f| to_var_name(&f.field_ident));
-        let async_quote = if async_field_dis.len() > 1 {
-            quote! {
-                let (#(#async_var_names),*) = futures::join!(#(#async_di_exprs),*);
-            }
-        } else if async_field_dis.len() == 1 {
-            quote! {
-                let #(#async_var_names)* = #(#async_di_exprs)*.await;
+    let sync_exprs = field_dis
+        .iter()
+        .map(|f| f.sync_expr())
+        .collect::<Option<Vec<_>>>();
+    // an async struct doesn't implement `create_sync`. (see `SyncToken`)
+    let create_sync_quote = match sync_exprs {
+        Some(sync_exprs) if !is_always_async => quote! {
+            #[allow(unused_variables)]
+            fn create_sync(container: &portaldi::DIContainer, token: portaldi::SyncToken<Self::Mode>) -> Self {
+                #(let #var_names = #sync_exprs;)*
+                #construct_quote
             }
-        } else {
-            quote! {}
-        };
-        let mut sync_quotes = sync_field_dis
-            .iter()
-            .map(|f| {
-                let ident = &f.field_ident;
-                let var_name = to_var_name(&ident);
-                let expr = &f.di_expr;
-                quote! {
-                    let #var_name = #expr;
-                }
-            })
-            .collect::<Vec<_>>();
-        let mut result: Vec<proc_macro2::TokenStream> = vec![];
-        result.append(&mut sync_quotes);
-        result.push(async_quote);
-        result
-    } else {
-        field_dis
-            .iter()
-            .map(|f| {
-                let ident = &f.field_ident;
-                let var_name = to_var_name(&ident);
-                let expr = &f.di_expr;
-                if f.is_async {
-                    quote! {
-                        let #var_name = #expr.await;
-                    }
-                } else {
-                    quote! {
-                        let #var_name = #expr;
-                    }
-                }
-            })
-            .collect::<Vec<_>>()
+        },
+        _ => quote!(),
     };
 
-    let field_idents = field_dis.iter().map(|f| {
-        let ident = &f.field_ident;
-        let var_name = to_var_name(&f.field_ident);
+    // async dependencies are resolved concurrently.
+    let (async_field_dis, sync_field_dis): (Vec<_>, Vec<_>) =
+        field_dis.iter().partition(|f| f.async_expr().1);
+
+    let async_di_exprs = async_field_dis.iter().map(|f| f.async_expr().0);
+    let async_var_names = async_field_dis.iter().map(|f| f.var_name());
+    let async_quote = if async_field_dis.len() > 1 {
         quote! {
-            #ident: #var_name
+            let (#(#async_var_names),*) = portaldi::join!(#(#async_di_exprs),*);
         }
-    });
-    if is_totally_async {
-        let async_trait_attr = async_trait_attr();
-
+    } else if async_field_dis.len() == 1 {
         quote! {
-            #async_trait_attr
-            impl portaldi::AsyncDIPortal for #ident {
-                async fn create_for_di(container: &portaldi::DIContainer) -> Self {
-                    #(#di_var_quotes)*
-                    #ident { #(#field_idents),* }
-                }
-            }
+            let #(#async_var_names)* = #(#async_di_exprs)*.await;
         }
     } else {
-        quote! {
-            impl portaldi::DIPortal for #ident {
-                fn create_for_di(container: &portaldi::DIContainer) -> Self {
-                    #(#di_var_quotes)*
-                    #ident { #(#field_idents),* }
-                }
+        quote! {}
+    };
+    let mut di_var_quotes = sync_field_dis
+        .iter()
+        .map(|f| {
+            let var_name = f.var_name();
+            let expr = f.async_expr().0;
+            quote! {
+                let #var_name = #expr;
             }
-        }
-    }
-}
+        })
+        .collect::<Vec<_>>();
+    di_var_quotes.push(async_quote);
 
-#[derive(PartialEq)]
-enum InjectAttrPart {
-    Path(Path),
-    Async,
-}
+    let mode_type = build_mode_type(&field_dis, is_always_async);
+    let (scoped_quote, static_singleton_quote) = if is_scoped {
+        (
+            quote!(
+                const SCOPED: bool = true;
+            ),
+            quote!(),
+        )
+    } else {
+        (quote!(), build_static_singleton(&parse_quote!(#ident)))
+    };
+    let async_trait_attr = async_trait_attr();
 
-impl Parse for InjectAttrPart {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        Ok(if input.peek(Token![async]) {
-            input.parse::<Token![async]>()?;
-            InjectAttrPart::Async
-        } else {
-            InjectAttrPart::Path(input.parse()?)
-        })
+    quote! {
+        #async_trait_attr
+        impl portaldi::AutoDIPortal for #ident {
+            type Mode = #mode_type;
+            #scoped_quote
+            #static_singleton_quote
+            #create_sync_quote
+            async fn create_async(container: &portaldi::DIContainer) -> Self {
+                #(#di_var_quotes)*
+                #construct_quote
+            }
+        }
     }
 }
@@ -4,9 +4,10 @@ macro_rules! define {
         ///
         /// ```ignore
         /// di![Hoge]               // => HogeProvider::di()
-        /// di![Hoge<String, bool>] // => HogeStringboolProvider::di()
-        /// di![Hoge<String, ()>]   // => HogeStringUnitProvider::di()
+        /// di![Hoge<String, bool>] // => Hoge_GString_Sbool_EProvider::di()
+        /// di![Hoge<String, ()>]   // => Hoge_GString_S_U_EProvider::di()
         /// di![Hoge on c]          // => HogeProvider::di_on(c)
+        /// di![crate::app::Hoge]   // => crate::app::HogeProvider::di()
         /// ```
         #[proc_macro]
         #[allow(non_snake_case)]
@@ -18,43 +19,40 @@ macro_rules! define {
 pub(crate) use define;
 
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
+use quote::quote;
 use syn::{
     parse::{Parse, ParseStream},
     parse2,
 };
 
 use crate::helper::kw;
-use crate::helper::Generics_;
+use crate::helper::ProvideTarget;
 
 pub fn exec(input: TokenStream2) -> TokenStream2 {
-    let DiInput {
-        target_ident,
-        generics,
-        arg,
-    } = parse2::<DiInput>(input).unwrap();
+    expand(input).unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn expand(input: TokenStream2) -> syn::Result<TokenStream2> {
+    let DiInput { target, arg } = parse2::<DiInput>(input)?;
 
-    let type_params_str = generics.type_params_str();
-    let provider_type_name = format_ident!("{}{}Provider", target_ident, type_params_str);
+    let provider_type_name = target.provider_path()?;
 
-    if let Some(arg) = arg.as_ref() {
+    Ok(if let Some(arg) = arg.as_ref() {
         quote!(#provider_type_name :: di_on(#arg))
     } else {
         quote!(#provider_type_name :: di())
-    }
+    })
 }
 
 #[derive(Debug)]
 pub struct DiInput {
-    pub target_ident: syn::Ident,
-    pub generics: Generics_,
+    pub target: ProvideTarget,
     pub arg: Option<syn::Expr>,
 }
 
 impl Parse for DiInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let target_ident = input.parse()?;
-        let generics = input.parse()?;
+        let target = input.parse()?;
         let arg = if input.peek(kw::on) {
             let _: kw::on = input.parse()?;
             let arg = input.parse()?;
@@ -63,10 +61,6 @@ impl Parse for DiInput {
             None
         };
 
-        Ok(DiInput {
-            target_ident,
-            generics,
-            arg,
-        })
+        Ok(DiInput { target, arg })
     }
 }
@@ -4,14 +4,16 @@ use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
     token::Comma,
-    Ident, Token, Type, TypePath, TypeTuple,
+    GenericArgument, Ident, LitStr, Path, Token,
 };
 
+use crate::helper::{kw, provider_ident, provider_path};
+
 // syn::Generics では unit を解決できなかったので自前で実装
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Generics_ {
     pub lt: Option<Token![<]>,
-    pub params: Punctuated<Type, Comma>,
+    pub params: Punctuated<GenericArgument, Comma>,
     pub gt: Option<Token![>]>,
 }
 
@@ -19,13 +21,14 @@ impl Parse for Generics_ {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         Ok(if input.peek(Token![<]) {
             let lt: Token![<] = input.parse()?;
-            let mut params: Punctuated<Type, Comma> = Punctuated::new();
+            let mut params: Punctuated<GenericArgument, Comma> = Punctuated::new();
             let gt: Token![>];
             loop {
-                let t: Type = input.parse()?;
-                params.push(t);
+                params.push(input.parse()?);
                 if input.peek(Comma) {
                     let _: Comma = input.parse()?;
+                } else if !input.peek(Token![>]) {
+                    return Err(input.error("expected `,` or `>`"));
                 }
                 if input.peek(Token![>]) {
                     gt = input.parse()?;
@@ -56,26 +59,16 @@ impl ToTokens for Generics_ {
 }
 
 impl Generics_ {
-    pub fn type_params_str(&self) -> String {
-        self.params
-            .iter()
-            .flat_map(|p| match p {
-                Type::Path(TypePath { path, .. }) => Some(path.to_token_stream().to_string()),
-                Type::Tuple(TypeTuple { elems, .. }) if elems.is_empty() => {
-                    Some("Unit".to_string())
-                }
-                _ => None,
-            })
-            .collect::<Vec<_>>()
-            .concat()
+    /// Provider ident for `ident` with these generics.
+    pub fn provider_ident(&self, ident: &Ident) -> syn::Result<Ident> {
+        provider_ident(ident, &self.params)
     }
 }
 
 #[derive(Debug)]
 pub struct DefDiProviderInput {
     pub kw_dyn: Option<Token![dyn]>,
-    pub target_ident: syn::Ident,
-    pub generics: Generics_,
+    pub target: ProvideTarget,
     pub _comma: Token![,],
     pub create_fn: syn::ExprClosure,
 }
@@ -87,31 +80,111 @@ impl Parse for DefDiProviderInput {
         } else {
             None
         };
-        let target_ident = input.parse()?;
-        let generics = input.parse()?;
+        let target = input.parse()?;
         let _comma = input.parse()?;
         let create_fn = input.parse()?;
 
         Ok(DefDiProviderInput {
             kw_dyn,
-            target_ident,
-            generics,
+            target,
             _comma,
             create_fn,
         })
     }
 }
 
+/// A target type of a provider, e.g. `FooI`, `FooI<A>` or `crate::service::FooI<A>`.
 #[derive(Debug)]
 pub struct ProvideTarget {
-    pub ident: Ident,
+    pub path: Path,
     pub generics: Generics_,
 }
 
 impl Parse for ProvideTarget {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let ident = input.parse()?;
+        let path = Path::parse_mod_style(input)?;
         let generics = input.parse()?;
-        Ok(Self { ident, generics })
+        Ok(Self { path, generics })
+    }
+}
+
+impl ToTokens for ProvideTarget {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.path.to_tokens(tokens);
+        self.generics.to_tokens(tokens);
+    }
+}
+
+impl ProvideTarget {
+    pub fn new(ident: Ident, generics: Generics_) -> Self {
+        Self {
+            path: ident.into(),
+            generics,
+        }
+    }
+
+    /// Provider ident, which is generated next to the implementation.
+    pub fn provider_ident(&self) -> syn::Result<Ident> {
+        // a mod style path always has a last segment.
+        let last = self.path.segments.last().unwrap();
+        self.generics.provider_ident(&last.ident)
+    }
+
+    /// Provider path, which resolves in the same module as the target type.
+    pub fn provider_path(&self) -> syn::Result<Path> {
+        provider_path(&self.path, &self.generics.params)
+    }
+}
+
+/// Provide targets with an optional name of their bindings, e.g. `FooI, BarI, name = "foo"`,
+/// or `default` to register the providers on the traits, e.g. `FooI, default`.
+#[derive(Debug)]
+pub struct ProvideTargets {
+    pub targets: Punctuated<ProvideTarget, Comma>,
+    pub name: Option<LitStr>,
+    pub default: Option<kw::default>,
+}
+
+impl Parse for ProvideTargets {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut targets = Punctuated::new();
+        let mut name = None;
+        let mut default = None;
+        while !input.is_empty() {
+            if input.peek(kw::name) && input.peek2(Token![=]) {
+                let _: kw::name = input.parse()?;
+                let _: Token![=] = input.parse()?;
+                name = Some(input.parse()?);
+            } else if input.peek(kw::default)
+                && (input.peek2(Comma) || input.peek2(syn::parse::End))
+            {
+                default = Some(input.parse()?);
+            } else if name.is_some() || default.is_some() {
+                return Err(input.error("`name` and `default` must follow the traits"));
+            } else {
+                targets.push_value(input.parse()?);
+            }
+            if input.is_empty() {
+                break;
+            }
+            let comma: Comma = input.parse()?;
+            if name.is_none() && default.is_none() {
+                targets.push_punct(comma);
+            }
+        }
+        if targets.is_empty() {
+            return Err(input.error("expected a trait to provide"));
+        }
+        if let (Some(_), Some(default)) = (&name, &default) {
+            return Err(syn::Error::new_spanned(
+                default,
+                "a named provider can't be the default one of the traits",
+            ));
+        }
+        Ok(Self {
+            targets,
+            name,
+            default,
+        })
     }
 }
@@ -0,0 +1,478 @@
+//! Macro configuration of a crate.
+//!
+//! Settings are read from `[package.metadata.portaldi]` in `Cargo.toml` of the crate,
+//! or otherwise from the nearest `portaldi.toml` in the crate directory or its ancestors up to the workspace root
+//! (the nearest directory whose `Cargo.toml` has `[workspace]`), so that crates in a workspace can share one file.
+//!
+//! ```toml
+//! always-async = true               # generate AsyncDIPortal for all derived components.
+//! default-visibility = "pub(crate)" # visibility of generated providers. (default: "pub")
+//! default-lifetime = "scoped"       # lifetime of derived components. (default: "singleton")
+//! provider-patterns = [
+//!     # `FooImpl` provides `dyn Foo`.
+//!     "^(\\w+)Impl$",
+//!     # `SqlUserRepo` provides `dyn Repo<User>`.
+//!     { pattern = "^Sql(\\w+)Repo$", target = "Repo<$1>" },
+//! ]
+//! ```
+//!
+//! For settings absent in the configuration, `PORTALDI_ALWAYS_ASYNC` and `PORTALDI_PROVIDER_PATTERN`
+//! environment variables are read for compatibility.
+//!
+//! Generated code tracks the configuration file, `Cargo.toml` of the workspace root and the environment variables read,
+//! so that a change of them (including a new configuration file in the workspace) rebuilds the crate.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use regex::Regex;
+use serde::Deserialize;
+use syn::{Ident, Visibility};
+
+use crate::helper::{Generics_, ProvideTarget};
+
+const CONFIG_FILE: &str = "portaldi.toml";
+const ALWAYS_ASYNC_ENV: &str = "PORTALDI_ALWAYS_ASYNC";
+const PROVIDER_PATTERN_ENV: &str = "PORTALDI_PROVIDER_PATTERN";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct RawConfig {
+    always_async: Option<bool>,
+    provider_patterns: Option<Vec<RawProviderPattern>>,
+    default_visibility: Option<String>,
+    default_lifetime: Option<Lifetime>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawProviderPattern {
+    Capture(String),
+    Template { pattern: String, target: String },
+}
+
+/// Lifetime of derived components.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Lifetime {
+    /// A singleton in the root container.
+    #[default]
+    Singleton,
+    /// A component per scope.
+    Scoped,
+}
+
+/// Resolved configuration of a crate.
+#[derive(Debug, Default)]
+pub struct Config {
+    pub always_async: bool,
+    pub provider_patterns: Vec<ProviderPattern>,
+    pub default_lifetime: Lifetime,
+    default_visibility: Option<String>,
+    /// Configuration file, which is tracked for rebuilds.
+    file: Option<PathBuf>,
+    /// `Cargo.toml` of the workspace root except the crate itself, which is tracked for rebuilds.
+    workspace_manifest: Option<PathBuf>,
+    /// Environment variables read for absent settings, which are tracked for rebuilds.
+    env_vars: Vec<&'static str>,
+}
+
+thread_local! {
+    static CONFIGS: RefCell<HashMap<PathBuf, Rc<Config>>> = RefCell::new(HashMap::new());
+}
+
+/// Get the configuration of the crate being compiled.
+pub fn config() -> syn::Result<Rc<Config>> {
+    let dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    if let Some(config) = CONFIGS.with(|c| c.borrow().get(&dir).cloned()) {
+        return Ok(config);
+    }
+    let config = Rc::new(load(&dir).map_err(|e| syn::Error::new(Span::call_site(), e))?);
+    CONFIGS.with(|c| c.borrow_mut().insert(dir, config.clone()));
+    Ok(config)
+}
+
+fn load(dir: &Path) -> Result<Config, String> {
+    let manifest = dir.join("Cargo.toml");
+    let workspace_root = dir.ancestors().find(|d| is_workspace_root(d));
+    let (raw, file) = match read_metadata(&manifest)? {
+        Some(raw) => (raw, None),
+        None => match find_file(dir, workspace_root.unwrap_or(dir)) {
+            Some(file) => (read_file(&file)?, Some(file)),
+            None => (RawConfig::default(), None),
+        },
+    };
+    let source = match &file {
+        Some(file) => file.display().to_string(),
+        None => format!("[package.metadata.portaldi] of {}", manifest.display()),
+    };
+    let mut config = Config::resolve(raw, file, &source)?;
+    config.workspace_manifest = workspace_root
+        .filter(|root| *root != dir)
+        .map(|root| root.join("Cargo.toml"));
+    Ok(config)
+}
+
+/// Find the nearest configuration file in `dir` or its ancestors up to the workspace root.
+/// Without a workspace, only `dir` is searched.
+fn find_file(dir: &Path, root: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .take_while(|d| d.starts_with(root))
+        .map(|d| d.join(CONFIG_FILE))
+        .find(|f| f.is_file())
+}
+
+fn is_workspace_root(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Table>(&content).ok())
+        .is_some_and(|table| table.contains_key("workspace"))
+}
+
+fn read_metadata(manifest: &Path) -> Result<Option<RawConfig>, String> {
+    let Ok(content) = std::fs::read_to_string(manifest) else {
+        return Ok(None);
+    };
+    let invalid = |e: &dyn std::fmt::Display| {
+        format!(
+            "invalid portaldi configuration in [package.metadata.portaldi] of {}: {e}",
+            manifest.display()
+        )
+    };
+    let mut table = toml::from_str::<toml::Table>(&content).map_err(|e| invalid(&e))?;
+    let metadata = table
+        .remove("package")
+        .and_then(|mut p| p.as_table_mut()?.remove("metadata"))
+        .and_then(|mut m| m.as_table_mut()?.remove("portaldi"));
+    metadata
+        .map(|m| m.try_into::<RawConfig>().map_err(|e| invalid(&e)))
+        .transpose()
+}
+
+fn read_file(file: &Path) -> Result<RawConfig, String> {
+    std::fs::read_to_string(file)
+        .map_err(|e| e.to_string())
+        .and_then(|content| toml::from_str(&content).map_err(|e| e.to_string()))
+        .map_err(|e| format!("invalid portaldi configuration in {}: {e}", file.display()))
+}
+
+impl Config {
+    fn resolve(raw: RawConfig, file: Option<PathBuf>, source: &str) -> Result<Self, String> {
+        let invalid = |e: String| format!("invalid portaldi configuration in {source}: {e}");
+        let mut env_vars = vec![];
+        let always_async = match raw.always_async {
+            Some(always_async) => always_async,
+            None => {
+                env_vars.push(ALWAYS_ASYNC_ENV);
+                std::env::var(ALWAYS_ASYNC_ENV)
+                    .ok()
+                    .and_then(|s| s.parse::<bool>().ok())
+                    .unwrap_or(false)
+            }
+        };
+
+        let provider_patterns = match raw.provider_patterns {
+            Some(patterns) => patterns
+                .into_iter()
+                .map(|p| match p {
+                    RawProviderPattern::Capture(pattern) => {
+                        ProviderPattern::new("provider-patterns", pattern, None)
+                    }
+                    RawProviderPattern::Template { pattern, target } => {
+                        ProviderPattern::new("provider-patterns", pattern, Some(target))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(invalid)?,
+            None => {
+                env_vars.push(PROVIDER_PATTERN_ENV);
+                match std::env::var(PROVIDER_PATTERN_ENV) {
+                    Ok(pattern) => vec![ProviderPattern::new(PROVIDER_PATTERN_ENV, pattern, None)?],
+                    Err(_) => vec![],
+                }
+            }
+        };
+
+        if let Some(vis) = &raw.default_visibility {
+            syn::parse_str::<Visibility>(vis).map_err(|_| {
+                invalid(format!(
+                    "default-visibility `{vis}` is not a visibility, e.g. \"pub(crate)\""
+                ))
+            })?;
+        }
+
+        Ok(Config {
+            always_async,
+            provider_patterns,
+            default_lifetime: raw.default_lifetime.unwrap_or_default(),
+            default_visibility: raw.default_visibility,
+            file,
+            workspace_manifest: None,
+            env_vars,
+        })
+    }
+
+    /// Visibility of generated providers, unless specified by the target.
+    pub fn default_visibility(&self) -> TokenStream {
+        match &self.default_visibility {
+            // validated on load.
+            Some(vis) => {
+                let vis = syn::parse_str::<Visibility>(vis).unwrap();
+                quote!(#vis)
+            }
+            None => quote!(pub),
+        }
+    }
+
+    /// Track the configuration file, `Cargo.toml` of the workspace root and environment variables read,
+    /// so that a change of them rebuilds the crate.
+    pub fn tracking(&self) -> TokenStream {
+        let files = self
+            .file
+            .iter()
+            .chain(&self.workspace_manifest)
+            .filter_map(|f| f.to_str());
+        let env_vars = &self.env_vars;
+        quote! {
+            #(const _: &[u8] = include_bytes!(#files);)*
+            #(const _: Option<&str> = option_env!(#env_vars);)*
+        }
+    }
+}
+
+/// A pattern of type names, which provide a trait named from the type name.
+#[derive(Debug)]
+pub struct ProviderPattern {
+    regex: Regex,
+    /// Target with `$1` style references to capture groups, or the first capture group if absent.
+    target: Option<String>,
+}
+
+impl ProviderPattern {
+    fn new(setting: &str, pattern: String, target: Option<String>) -> Result<Self, String> {
+        let regex =
+            Regex::new(&pattern).map_err(|e| format!("invalid {setting} `{pattern}`: {e}"))?;
+        if target.is_none() && regex.captures_len() < 2 {
+            return Err(format!(
+                "{setting} `{pattern}` must have a capture group for the trait name"
+            ));
+        }
+        Ok(Self { regex, target })
+    }
+
+    /// Provide target for a type, if the type name matches.
+    /// Type arguments of the type are carried to a target without type arguments,
+    /// e.g. `RepoImpl<User>` provides `dyn Repo<User>` by `^(\w+)Impl$`.
+    pub fn target_of(&self, ident: &Ident, args: &Generics_) -> syn::Result<Option<ProvideTarget>> {
+        let ident_str = ident.to_string();
+        let Some(cap) = self.regex.captures(&ident_str) else {
+            return Ok(None);
+        };
+        let target = match &self.target {
+            Some(template) => {
+                let mut target = String::new();
+                cap.expand(template, &mut target);
+                target
+            }
+            // a capture group is validated on load, but it may not participate in the match.
+            None => cap
+                .get(1)
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+        };
+        let mut provide_target = target
+            .parse::<TokenStream>()
+            .ok()
+            .map(|tokens| respan(tokens, ident.span()))
+            .and_then(|tokens| syn::parse2::<ProvideTarget>(tokens).ok())
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    ident,
+                    format!(
+                        "provider pattern `{}` derived `{}`, which is not a trait name",
+                        self.regex, target
+                    ),
+                )
+            })?;
+        if provide_target.generics.params.is_empty() {
+            provide_target.generics = args.clone();
+        }
+        Ok(Some(provide_target))
+    }
+}
+
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut tt| {
+            tt.set_span(span);
+            tt
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(content: &str) -> Result<Config, String> {
+        Config::resolve(
+            toml::from_str(content).map_err(|e| e.to_string())?,
+            None,
+            CONFIG_FILE,
+        )
+    }
+
+    fn target_of(config: &Config, name: &str) -> Vec<String> {
+        let ident = Ident::new(name, Span::call_site());
+        config
+            .provider_patterns
+            .iter()
+            .filter_map(|p| p.target_of(&ident, &Generics_::default()).unwrap())
+            .map(|t| quote!(#t).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let config = resolve(
+            r#"
+            always-async = true
+            default-visibility = "pub(crate)"
+            default-lifetime = "scoped"
+            provider-patterns = [
+                "^(\\w+)Impl$",
+                { pattern = "^(\\w+)RepoImpl$", target = "Repo<$1>" },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert!(config.always_async);
+        assert_eq!(config.default_lifetime, Lifetime::Scoped);
+        assert_eq!(config.default_visibility().to_string(), "pub (crate)");
+        assert_eq!(target_of(&config, "FooImpl"), vec!["Foo"]);
+        assert_eq!(
+            target_of(&config, "UserRepoImpl"),
+            vec!["UserRepo", "Repo < User >"]
+        );
+        assert!(target_of(&config, "Foo").is_empty());
+    }
+
+    #[test]
+    fn test_carry_type_args() {
+        let config = resolve(
+            r#"
+            provider-patterns = [
+                "^(\\w+)Impl$",
+                { pattern = "^Pg(\\w+)$", target = "Repo<String>" },
+            ]
+            "#,
+        )
+        .unwrap();
+        let ident = Ident::new("PgRepoImpl", Span::call_site());
+        let targets = config
+            .provider_patterns
+            .iter()
+            .filter_map(|p| p.target_of(&ident, &syn::parse_quote!(<User>)).unwrap())
+            .map(|t| quote!(#t).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(targets, vec!["PgRepo < User >", "Repo < String >"]);
+    }
+
+    #[test]
+    fn test_resolve_default() {
+        let config = resolve("").unwrap();
+        assert_eq!(config.default_lifetime, Lifetime::Singleton);
+        assert_eq!(config.default_visibility().to_string(), "pub");
+    }
+
+    #[test]
+    fn test_tracking_env_vars() {
+        let tracking = |content: &str| resolve(content).unwrap().tracking().to_string();
+        let all = tracking("");
+        assert!(all.contains("option_env ! (\"PORTALDI_ALWAYS_ASYNC\")"));
+        assert!(all.contains("option_env ! (\"PORTALDI_PROVIDER_PATTERN\")"));
+        // environment variables aren't read for settings in the configuration.
+        let patterns = tracking("always-async = false");
+        assert!(!patterns.contains("PORTALDI_ALWAYS_ASYNC"));
+        assert!(patterns.contains("PORTALDI_PROVIDER_PATTERN"));
+        assert!(tracking("always-async = false\nprovider-patterns = []").is_empty());
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        let err = |content: &str| resolve(content).unwrap_err();
+        assert!(err("always-async = 1").contains("invalid type"));
+        assert!(err("unknown = true").contains("unknown field `unknown`"));
+        assert!(err(r#"default-lifetime = "request""#).contains("unknown variant `request`"));
+        assert!(err(r#"default-visibility = "public""#).contains("is not a visibility"));
+        assert!(err(r#"provider-patterns = ["("]"#).contains("invalid provider-patterns `(`"));
+        assert!(err(r#"provider-patterns = ["Impl$"]"#).contains("must have a capture group"));
+    }
+
+    #[test]
+    fn test_invalid_target() {
+        let config =
+            resolve(r#"provider-patterns = [{ pattern = "Impl$", target = "1<" }]"#).unwrap();
+        let ident = Ident::new("FooImpl", Span::call_site());
+        let err = config.provider_patterns[0]
+            .target_of(&ident, &Generics_::default())
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("derived `1<`, which is not a trait name"));
+    }
+
+    #[test]
+    fn test_load() {
+        let workspace =
+            std::env::temp_dir().join(format!("portaldi_config_{}", std::process::id()));
+        let dir = workspace.join("foo");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(workspace.join(CONFIG_FILE), "default-lifetime = \"scoped\"").unwrap();
+
+        // the file in an ancestor directory.
+        std::fs::write(dir.join("Cargo.toml"), "[package]\nname = \"foo\"").unwrap();
+        std::fs::write(workspace.join("Cargo.toml"), "[workspace]").unwrap();
+        let config = load(&dir).unwrap();
+        assert_eq!(config.default_lifetime, Lifetime::Scoped);
+        assert_eq!(config.file, Some(workspace.join(CONFIG_FILE)));
+        assert_eq!(
+            config.workspace_manifest,
+            Some(workspace.join("Cargo.toml"))
+        );
+
+        // a file beyond the workspace root isn't used.
+        let member = workspace.join("nested").join("foo");
+        std::fs::create_dir_all(&member).unwrap();
+        std::fs::write(member.join("Cargo.toml"), "[package]\nname = \"foo\"").unwrap();
+        std::fs::write(workspace.join("nested").join("Cargo.toml"), "[workspace]").unwrap();
+        let config = load(&member).unwrap();
+        assert_eq!(config.default_lifetime, Lifetime::Singleton);
+        assert_eq!(config.file, None);
+        // the workspace root is tracked without the file, so that a new file rebuilds the crate.
+        let tracking = config.tracking().to_string();
+        assert!(tracking.contains(&format!(
+            "{:?}",
+            workspace.join("nested").join("Cargo.toml")
+        )));
+
+        // the metadata takes precedence over the file.
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"foo\"\n[package.metadata.portaldi]\ndefault-lifetime = \"singleton\"",
+        )
+        .unwrap();
+        let config = load(&dir).unwrap();
+        assert_eq!(config.default_lifetime, Lifetime::Singleton);
+        assert_eq!(config.file, None);
+
+        std::fs::remove_dir_all(&workspace).unwrap();
+    }
+}
@@ -0,0 +1,190 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, GenericArgument, Meta, Path, PathArguments, Token, Type, TypeParamBound,
+};
+
+use crate::helper::{attr_of, provider_path};
+
+/// Settings of `#[inject(..)]`.
+pub struct InjectAttr {
+    pub path: Option<Path>,
+    pub is_async: bool,
+}
+
+pub fn parse_inject_attr(attrs: &[Attribute]) -> syn::Result<Option<InjectAttr>> {
+    let attr = match attr_of(attrs, "inject") {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+    let metas = match &attr.meta {
+        Meta::List(metas) => metas,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected arguments, e.g. #[inject(async)], #[inject(Foo)] or #[inject(FooProvider, async)]",
+            ))
+        }
+    };
+    let args = metas.parse_args_with(Punctuated::<InjectAttrPart, Token![,]>::parse_terminated)?;
+
+    let mut path: Option<Path> = None;
+    let mut is_async = false;
+    for arg in args {
+        match arg {
+            InjectAttrPart::Async(kw) if is_async => {
+                return Err(syn::Error::new_spanned(
+                    kw,
+                    "duplicate `async` in #[inject]",
+                ))
+            }
+            InjectAttrPart::Async(_) => is_async = true,
+            InjectAttrPart::Path(p) if path.is_some() => {
+                return Err(syn::Error::new_spanned(
+                    p,
+                    "#[inject] accepts only one type or provider",
+                ))
+            }
+            InjectAttrPart::Path(p) => path = Some(p),
+        }
+    }
+
+    Ok(Some(InjectAttr { path, is_async }))
+}
+
+/// Build an expression that injects `ty` from `container`.
+pub fn build_di_expr(
+    ty: &Type,
+    inject_path: Option<&Path>,
+    is_async: bool,
+) -> syn::Result<TokenStream> {
+    if let Some(path) = inject_path {
+        return Ok(quote! {
+            #path::di_on(container)
+        });
+    }
+
+    let di_provider_type = di_provider_path(ty)?;
+    let provider_trait = if is_async {
+        quote!(AsyncDIProvider)
+    } else {
+        quote!(DIProvider)
+    };
+    Ok(quote_spanned! {ty.span()=>
+        <#di_provider_type as portaldi::#provider_trait>::di_on(container)
+    })
+}
+
+/// Expressions that resolve `ty` from `container` through its provider, whose mode is inferred.
+/// A hand-written `DIProvider` or `AsyncDIProvider` is used, too. (see `ProviderResolution`)
+pub struct ResolverExprs {
+    /// Expression in a sync method, which takes `token` of the mode.
+    pub sync_expr: TokenStream,
+    pub async_expr: TokenStream,
+    /// `IS_ASYNC` of the mode.
+    pub is_async_expr: TokenStream,
+}
+
+pub fn build_resolver_exprs(ty: &Type) -> syn::Result<ResolverExprs> {
+    let p = di_provider_path(ty)?;
+    Ok(build_provider_resolver_exprs(&p, ty.span()))
+}
+
+/// `ResolverExprs` through a provider, whose errors are reported on `span`.
+pub fn build_provider_resolver_exprs(provider: &Path, span: Span) -> ResolverExprs {
+    let resolution = |item: TokenStream| {
+        quote_spanned! {span=>
+            {
+                #[allow(unused_imports)]
+                use portaldi::{ResolveByAsyncDIProvider as _, ResolveByDIProvider as _};
+                portaldi::ProviderResolution::<#provider>::#item
+            }
+        }
+    };
+    ResolverExprs {
+        sync_expr: resolution(quote!(resolve_sync(container, token.cast()))),
+        async_expr: resolution(quote!(resolve_async(container))),
+        is_async_expr: resolution(quote!(IS_ASYNC)),
+    }
+}
+
+/// Path of the implicit provider of `DI<T>`.
+fn di_provider_path(ty: &Type) -> syn::Result<Path> {
+    let DIType { path, args } = get_di_type(ty)?;
+    // the provider path takes the span of the field type,
+    // so that an unresolved provider is reported on the field or parameter.
+    provider_path(path, &args)
+}
+
+pub struct DIType<'a> {
+    pub path: &'a Path,
+    pub args: Punctuated<GenericArgument, Token![,]>,
+}
+
+pub fn get_di_type(ty: &Type) -> syn::Result<DIType<'_>> {
+    let not_di_type = || {
+        syn::Error::new_spanned(
+            ty,
+            "injected type must be `DI<T>`; \
+             use #[inject(FooProvider)] to inject through a specific provider",
+        )
+    };
+
+    let last_path_segment = match ty {
+        Type::Path(x) => x.path.segments.last().ok_or_else(not_di_type)?,
+        _ => return Err(not_di_type()),
+    };
+    if last_path_segment.ident != "DI" {
+        return Err(not_di_type());
+    }
+    let arg = match &last_path_segment.arguments {
+        PathArguments::AngleBracketed(x) if x.args.len() == 1 => &x.args[0],
+        _ => return Err(not_di_type()),
+    };
+
+    let path = match arg {
+        GenericArgument::Type(Type::TraitObject(x)) => x
+            .bounds
+            .iter()
+            .find_map(|b| match b {
+                TypeParamBound::Trait(t) => Some(&t.path),
+                _ => None,
+            })
+            .ok_or_else(|| syn::Error::new_spanned(x, "trait object must name a trait"))?,
+        GenericArgument::Type(Type::Path(x)) if x.qself.is_none() => &x.path,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                arg,
+                "unsupported DI target type: expected `DI<Foo>` or `DI<dyn FooI>`; \
+                 use #[inject(FooProvider)] to inject through a specific provider",
+            ))
+        }
+    };
+
+    let last_seg = path.segments.last().ok_or_else(not_di_type)?;
+    let args = match &last_seg.arguments {
+        PathArguments::AngleBracketed(x) => x.args.clone(),
+        PathArguments::None => Punctuated::new(),
+        PathArguments::Parenthesized(_) => return Err(not_di_type()),
+    };
+
+    Ok(DIType { path, args })
+}
+
+enum InjectAttrPart {
+    Path(Path),
+    Async(Token![async]),
+}
+
+impl Parse for InjectAttrPart {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(if input.peek(Token![async]) {
+            InjectAttrPart::Async(input.parse()?)
+        } else {
+            InjectAttrPart::Path(input.parse()?)
+        })
+    }
+}
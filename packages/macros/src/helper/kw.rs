@@ -1 +1,7 @@
 syn::custom_keyword!(on);
+syn::custom_keyword!(from);
+syn::custom_keyword!(include);
+syn::custom_keyword!(tag);
+syn::custom_keyword!(none);
+syn::custom_keyword!(name);
+syn::custom_keyword!(default);
@@ -4,7 +4,16 @@ pub use quotes::*;
 mod common;
 pub use common::*;
 
+mod config;
+pub use config::*;
+
 mod utils;
 pub use utils::*;
 
+mod inject;
+pub use inject::*;
+
+mod naming;
+pub use naming::*;
+
 pub mod kw;
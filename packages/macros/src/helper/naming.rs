@@ -0,0 +1,298 @@
+//! Provider naming.
+//!
+//! A provider for `Foo` is named `FooProvider`.
+//! When a target has type arguments, they are mangled into the name so that
+//! distinct targets never share a provider name.
+//!
+//! | syntax                 | mangled                          |
+//! |------------------------|----------------------------------|
+//! | `_` in an identifier   | `__`                             |
+//! | `Foo<A, B>`            | `Foo_GA_SB_E`                    |
+//! | `()`                   | `_U`                             |
+//! | `(A, B)`               | `_TA_SB_E`                       |
+//! | `&'a mut A`            | `_R_La_S_MA` (lifetime optional) |
+//! | `[A]`                  | `_QA`                            |
+//! | `[A; 4]`               | `_AA_S4_E`                       |
+//! | `dyn A + 'a`           | `_DA_S_La_E`                     |
+//! | `'a` as an argument    | `_La`                            |
+//! | `3` as an argument     | `_K3`                            |
+//! | `a::B` as an argument  | `a_PB`                           |
+//! | `::a::B`               | `_Ca_PB`                         |
+//!
+//! Paths in type arguments are kept as written, so `a::X` and `b::X` name distinct providers,
+//! and a type argument must be written by the same path where a provider is generated and where it is injected.
+//! A provider for `a::b::Foo` is looked up as `a::b::FooProvider`.
+
+use quote::format_ident;
+use syn::{
+    punctuated::Punctuated, token::Comma, Expr, ExprLit, GenericArgument, Ident, Lifetime, Lit,
+    Path, PathArguments, Type, TypeParamBound,
+};
+
+/// Build a provider ident for a target with its type arguments.
+pub fn provider_ident(
+    ident: &Ident,
+    args: &Punctuated<GenericArgument, Comma>,
+) -> syn::Result<Ident> {
+    let mut name = escape(ident);
+    if !args.is_empty() {
+        name.push_str(&mangle_args(args)?);
+    }
+    Ok(format_ident!("{}Provider", name, span = ident.span()))
+}
+
+/// Replace the last segment of a target type path with its provider ident.
+/// `crate::service::FooI` + `<A>` => `crate::service::FooI_GA_EProvider`
+pub fn provider_path(path: &Path, args: &Punctuated<GenericArgument, Comma>) -> syn::Result<Path> {
+    let mut provider_path = path.clone();
+    let last = provider_path
+        .segments
+        .pop()
+        .map(|p| p.into_value())
+        .ok_or_else(|| syn::Error::new_spanned(path, "empty path"))?;
+    if let Some(seg) = provider_path
+        .segments
+        .iter()
+        .find(|s| !s.arguments.is_empty())
+    {
+        return Err(syn::Error::new_spanned(
+            seg,
+            "type arguments are only allowed on the last path segment",
+        ));
+    }
+    provider_path
+        .segments
+        .push(provider_ident(&last.ident, args)?.into());
+    Ok(provider_path)
+}
+
+fn escape(ident: &Ident) -> String {
+    let s = ident.to_string();
+    s.strip_prefix("r#").unwrap_or(&s).replace('_', "__")
+}
+
+fn mangle_args<'a>(args: impl IntoIterator<Item = &'a GenericArgument>) -> syn::Result<String> {
+    let args = args
+        .into_iter()
+        .map(mangle_arg)
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(format!("_G{}_E", args.join("_S")))
+}
+
+fn mangle_arg(arg: &GenericArgument) -> syn::Result<String> {
+    match arg {
+        GenericArgument::Type(ty) => mangle_type(ty),
+        GenericArgument::Lifetime(lt) => Ok(mangle_lifetime(lt)),
+        GenericArgument::Const(expr) => Ok(format!("_K{}", mangle_const(expr)?)),
+        _ => Err(unsupported(arg)),
+    }
+}
+
+fn mangle_type(ty: &Type) -> syn::Result<String> {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => mangle_path(&p.path),
+        Type::Tuple(t) if t.elems.is_empty() => Ok("_U".to_string()),
+        Type::Tuple(t) => {
+            let elems = t
+                .elems
+                .iter()
+                .map(mangle_type)
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(format!("_T{}_E", elems.join("_S")))
+        }
+        Type::Reference(r) => {
+            let lifetime = r
+                .lifetime
+                .as_ref()
+                .map(|lt| format!("{}_S", mangle_lifetime(lt)))
+                .unwrap_or_default();
+            let mutability = if r.mutability.is_some() { "_M" } else { "" };
+            Ok(format!(
+                "_R{}{}{}",
+                lifetime,
+                mutability,
+                mangle_type(&r.elem)?
+            ))
+        }
+        Type::Slice(s) => Ok(format!("_Q{}", mangle_type(&s.elem)?)),
+        Type::Array(a) => Ok(format!(
+            "_A{}_S{}_E",
+            mangle_type(&a.elem)?,
+            mangle_const(&a.len)?
+        )),
+        Type::TraitObject(t) => {
+            let bounds = t
+                .bounds
+                .iter()
+                .map(|b| match b {
+                    TypeParamBound::Trait(t) => mangle_path(&t.path),
+                    TypeParamBound::Lifetime(lt) => Ok(mangle_lifetime(lt)),
+                    _ => Err(unsupported(b)),
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(format!("_D{}_E", bounds.join("_S")))
+        }
+        Type::Paren(p) => mangle_type(&p.elem),
+        Type::Group(g) => mangle_type(&g.elem),
+        _ => Err(unsupported(ty)),
+    }
+}
+
+fn mangle_path(path: &Path) -> syn::Result<String> {
+    let segments = path
+        .segments
+        .iter()
+        .map(|s| {
+            let args = match &s.arguments {
+                PathArguments::None => String::new(),
+                PathArguments::AngleBracketed(a) => mangle_args(&a.args)?,
+                PathArguments::Parenthesized(_) => return Err(unsupported(path)),
+            };
+            Ok(format!("{}{}", escape(&s.ident), args))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let leading_colon = if path.leading_colon.is_some() {
+        "_C"
+    } else {
+        ""
+    };
+    Ok(format!("{}{}", leading_colon, segments.join("_P")))
+}
+
+fn mangle_lifetime(lt: &Lifetime) -> String {
+    format!("_L{}", escape(&lt.ident))
+}
+
+fn mangle_const(expr: &Expr) -> syn::Result<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(i), ..
+        }) => Ok(i.base10_digits().to_string()),
+        Expr::Lit(ExprLit {
+            lit: Lit::Bool(b), ..
+        }) => Ok(b.value.to_string()),
+        Expr::Path(p) if p.qself.is_none() => mangle_path(&p.path),
+        Expr::Block(b) if b.block.stmts.len() == 1 => match &b.block.stmts[0] {
+            syn::Stmt::Expr(e, None) => mangle_const(e),
+            _ => Err(unsupported(expr)),
+        },
+        _ => Err(unsupported(expr)),
+    }
+}
+
+fn unsupported(tokens: impl quote::ToTokens) -> syn::Error {
+    syn::Error::new_spanned(
+        tokens,
+        "unsupported type argument for a provider name; \
+         use paths, tuples, references, slices, arrays, trait objects, lifetimes or literals",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn name_of(ty: Type) -> String {
+        let Type::Path(p) = ty else { unreachable!() };
+        let last = p.path.segments.last().unwrap();
+        let args = match &last.arguments {
+            PathArguments::AngleBracketed(a) => a.args.clone(),
+            _ => Punctuated::new(),
+        };
+        provider_ident(&last.ident, &args).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_simple_names() {
+        assert_eq!(name_of(parse_quote!(FooI)), "FooIProvider");
+        assert_eq!(name_of(parse_quote!(a::b::FooI)), "FooIProvider");
+        assert_eq!(
+            name_of(parse_quote!(FooI<String, ()>)),
+            "FooI_GString_S_U_EProvider"
+        );
+        assert_eq!(
+            name_of(parse_quote!(FooI<std::string::String>)),
+            "FooI_Gstd_Pstring_PString_EProvider"
+        );
+    }
+
+    #[test]
+    fn test_distinct_names_for_distinct_types() {
+        let types: Vec<Type> = vec![
+            parse_quote!(FooI),
+            parse_quote!(Foo_I),
+            parse_quote!(FooI_GA_E),
+            parse_quote!(FooI<A>),
+            parse_quote!(FooI<AB>),
+            parse_quote!(FooI<A, B>),
+            parse_quote!(FooI<A_B>),
+            parse_quote!(FooI<A_SB>),
+            parse_quote!(FooI<A<B>>),
+            parse_quote!(FooI<A<B>, C>),
+            parse_quote!(FooI<A<B, C>>),
+            parse_quote!(FooI<Vec<u8>>),
+            parse_quote!(FooI<Vec<u16>>),
+            parse_quote!(FooI<()>),
+            parse_quote!(FooI<Unit>),
+            parse_quote!(FooI<(A,)>),
+            parse_quote!(FooI<(A, B)>),
+            parse_quote!(FooI<((A, B),)>),
+            parse_quote!(FooI<(A, (B,))>),
+            parse_quote!(FooI<&A>),
+            parse_quote!(FooI<&mut A>),
+            parse_quote!(FooI<&'static A>),
+            parse_quote!(FooI<&'a A>),
+            parse_quote!(FooI<&'a mut A>),
+            parse_quote!(FooI<&'static str>),
+            parse_quote!(FooI<'static, str>),
+            parse_quote!(FooI<'a>),
+            parse_quote!(FooI<[A]>),
+            parse_quote!(FooI<[A; 4]>),
+            parse_quote!(FooI<[A; 44]>),
+            parse_quote!(FooI<[[A; 4]; 4]>),
+            parse_quote!(FooI<4>),
+            parse_quote!(FooI<{ 4 }, A>),
+            parse_quote!(FooI<dyn A>),
+            parse_quote!(FooI<dyn A + Send>),
+            parse_quote!(FooI<dyn A<B>>),
+            parse_quote!(FooI<dyn A + 'static>),
+            parse_quote!(FooI<a::X>),
+            parse_quote!(FooI<b::X>),
+            parse_quote!(FooI<X>),
+            parse_quote!(FooI<::a::X>),
+            parse_quote!(FooI<a_PX>),
+            parse_quote!(FooI<a::X<B>>),
+            parse_quote!(FooI<a<B>::X>),
+            parse_quote!(FooI<dyn a::X>),
+            parse_quote!(FooI<dyn b::X>),
+            parse_quote!(FooI<{ a::N }>),
+            parse_quote!(FooI<{ b::N }>),
+        ];
+        let names: Vec<String> = types.into_iter().map(name_of).collect();
+        for (i, a) in names.iter().enumerate() {
+            for b in names.iter().skip(i + 1) {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsupported_types() {
+        let args: Punctuated<GenericArgument, Comma> = parse_quote!(fn(A) -> B);
+        assert!(provider_ident(&parse_quote!(FooI), &args).is_err());
+        let args: Punctuated<GenericArgument, Comma> = parse_quote!(impl A);
+        assert!(provider_ident(&parse_quote!(FooI), &args).is_err());
+    }
+
+    #[test]
+    fn test_provider_path() {
+        let path: Path = parse_quote!(crate::service::FooI);
+        let args: Punctuated<GenericArgument, Comma> = parse_quote!(A);
+        let expected: Path = parse_quote!(crate::service::FooI_GA_EProvider);
+        assert_eq!(provider_path(&path, &args).unwrap(), expected);
+
+        let path: Path = parse_quote!(service<A>::FooI);
+        assert!(provider_path(&path, &Punctuated::new()).is_err());
+    }
+}
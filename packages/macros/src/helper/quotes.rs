@@ -1,80 +1,304 @@
+use std::collections::{HashMap, HashSet};
+
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
-use regex::Regex;
-use syn::{Ident, Visibility};
+use quote::{quote, ToTokens};
+use syn::{Ident, LitStr, Type, Visibility};
+
+use crate::helper::{config, Generics_, ProvideTarget, ProvideTargets};
+
+/// How a provider resolves its component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveMode {
+    /// Through `DIPortal`.
+    Sync,
+    /// Through `AsyncDIPortal`.
+    Async,
+    /// Through `AutoDIPortal`, whose mode is inferred from dependencies.
+    Auto,
+}
 
-use crate::helper::{Generics_, ProvideTarget};
+impl ResolveMode {
+    pub fn from_async(is_async: bool) -> Self {
+        if is_async {
+            ResolveMode::Async
+        } else {
+            ResolveMode::Sync
+        }
+    }
+}
 
 pub fn build_provider(
     ident: &Ident,
     provide_target: &ProvideTarget,
-    is_async: bool,
+    mode: ResolveMode,
     for_trait: bool,
     vis: Option<&Visibility>,
-) -> TokenStream {
-    let type_params_str = provide_target.generics.type_params_str();
-    let provider_type = format_ident!("{}{}Provider", provide_target.ident, type_params_str);
-    let provide_target_ident = &provide_target.ident;
-    let provide_target_generics = &provide_target.generics;
+) -> syn::Result<TokenStream> {
+    let provider_type = provide_target.provider_ident()?;
     let dyn_keyword = if for_trait { Some(quote!(dyn)) } else { None };
-    let vis = vis.map(|vis| quote!(#vis)).unwrap_or(quote!(pub));
-    if is_async {
-        let asyn_trait_attr = async_trait_attr();
-        quote! {
-            #vis struct #provider_type;
+    let vis = match vis {
+        Some(vis) => quote!(#vis),
+        None => config()?.default_visibility(),
+    };
+    let (mode_type, sync_body, async_body) = resolve_bodies(ident, mode);
+    let resolver_quote = build_resolver(
+        &quote!(#provider_type),
+        &quote!(#dyn_keyword #provide_target),
+        &mode_type,
+        sync_body,
+        async_body,
+    );
+    Ok(quote! {
+        #[allow(non_camel_case_types)]
+        #vis struct #provider_type;
 
-            #asyn_trait_attr
-            impl portaldi::AsyncDIProvider for #provider_type {
-                type Output = #dyn_keyword #provide_target_ident #provide_target_generics;
-                async fn di_on(container: &portaldi::DIContainer) -> portaldi::DI<Self::Output> {
-                    #ident::di_on(container).await
-                }
+        #resolver_quote
+    })
+}
+
+/// Mode type and bodies of `resolve_sync` / `resolve_async` that resolve `ident` in the mode.
+fn resolve_bodies(
+    ident: &Ident,
+    mode: ResolveMode,
+) -> (TokenStream, Option<TokenStream>, TokenStream) {
+    match mode {
+        ResolveMode::Sync => (
+            quote!(portaldi::SyncMode),
+            Some(quote!(#ident::di_on(container))),
+            quote!(#ident::di_on(container)),
+        ),
+        ResolveMode::Async => (
+            quote!(portaldi::AsyncMode),
+            None,
+            quote!(#ident::di_on(container).await),
+        ),
+        ResolveMode::Auto => (
+            // through a const, so that the mode doesn't expose the type in a public provider.
+            quote!(portaldi::ResolveMode<{ <<#ident as portaldi::AutoDIPortal>::Mode as portaldi::DIMode>::IS_ASYNC }>),
+            Some(quote!(<#ident as portaldi::AutoDIPortal>::resolve_sync(container, token.cast()))),
+            quote!(<#ident as portaldi::AutoDIPortal>::resolve_async(container).await),
+        ),
+    }
+}
+
+/// Register a provider as the default one of a trait target, so that `ProviderOf<dyn Trait>` resolves it
+/// wherever it is generated. (see `DIProvided`)
+pub fn build_provided(provide_target: &ProvideTarget, provider_type: &TokenStream) -> TokenStream {
+    let resolve_async = quote!(<#provider_type as portaldi::DIResolver>::resolve_async(container));
+    let resolve_async = if cfg!(feature = "native-async") {
+        quote!(Box::pin(#resolve_async))
+    } else {
+        // `async_trait` already returns a boxed future.
+        resolve_async
+    };
+    // through a const and function bodies, so that a private provider doesn't leak.
+    quote! {
+        impl portaldi::DIProvided for dyn #provide_target {
+            type Mode = portaldi::ResolveMode<{ <<#provider_type as portaldi::DIResolver>::Mode as portaldi::DIMode>::IS_ASYNC }>;
+            fn resolve_sync(container: &portaldi::DIContainer, token: portaldi::SyncToken<Self::Mode>) -> portaldi::DI<Self> {
+                <#provider_type as portaldi::DIResolver>::resolve_sync(container, token.cast())
+            }
+            fn resolve_async(container: &portaldi::DIContainer) -> portaldi::BoxFuture<'_, portaldi::DI<Self>> {
+                #resolve_async
             }
         }
-    } else {
-        quote! {
-            #vis struct #provider_type;
+    }
+}
 
-            impl portaldi::DIProvider for #provider_type {
-                type Output = #dyn_keyword #provide_target_ident #provide_target_generics;
-                fn di_on(container: &portaldi::DIContainer) -> portaldi::DI<Self::Output> {
-                    #ident::di_on(container)
+/// Build a `DIResolver` implementation.
+/// `sync_body` is `None` for an async provider, which doesn't implement `resolve_sync`.
+/// (see `SyncToken`)
+pub fn build_resolver(
+    provider_type: &TokenStream,
+    output: &TokenStream,
+    mode_type: &TokenStream,
+    sync_body: Option<TokenStream>,
+    async_body: TokenStream,
+) -> TokenStream {
+    // registrations of a container take precedence.
+    let resolve_sync_quote = sync_body.map(|sync_body| {
+        quote! {
+            #[allow(unused_variables)]
+            fn resolve_sync(container: &portaldi::DIContainer, token: portaldi::SyncToken<Self::Mode>) -> portaldi::DI<Self::Output> {
+                if let Some(c) = container.resolve_registered::<Self::Output>() {
+                    return c;
                 }
+                #sync_body
+            }
+        }
+    });
+    let async_trait_attr = async_trait_attr();
+    quote! {
+        #async_trait_attr
+        impl portaldi::DIResolver for #provider_type {
+            type Output = #output;
+            type Mode = #mode_type;
+            #resolve_sync_quote
+            async fn resolve_async(container: &portaldi::DIContainer) -> portaldi::DI<Self::Output> {
+                if let Some(c) = container.resolve_registered_async::<Self::Output>().await {
+                    return c;
+                }
+                #async_body
             }
         }
     }
 }
 
-pub fn build_provider_by_env(ident: &Ident, is_async: bool) -> TokenStream {
-    let ident_str = &ident.to_string();
-    let provider_target_cap = std::env::var("PORTALDI_PROVIDER_PATTERN")
-        .ok()
-        .as_ref()
-        .and_then(|pattern| {
-            let re = Regex::new(pattern).unwrap();
-            re.captures(ident_str)
+/// Build providers for each trait, all backed by the singleton of `ident`.
+/// `args` are type arguments of the type, which are used for `IntoTarget` implementations.
+pub fn build_trait_providers<'a>(
+    ident: &Ident,
+    args: &Generics_,
+    provide_targets: impl IntoIterator<Item = &'a ProvideTarget>,
+    mode: ResolveMode,
+) -> syn::Result<TokenStream> {
+    let mut provider_types = HashMap::new();
+    let mut quotes = TokenStream::new();
+    for target in provide_targets {
+        let provider_type = target.provider_ident()?;
+        if let Some(other) = provider_types.insert(provider_type.to_string(), target) {
+            let (other, this) = (compact(other), compact(target));
+            let message = if other == this {
+                "duplicate provide target".to_string()
+            } else {
+                format!(
+                    "`{other}` and `{this}` generate the same provider `{provider_type}`; \
+                     provide them from separate modules"
+                )
+            };
+            return Err(syn::Error::new_spanned(target, message));
+        }
+        quotes.extend(build_provider(ident, target, mode, true, None)?);
+        quotes.extend(quote! {
+            impl portaldi::IntoTarget<dyn #target> for #ident #args {
+                fn into_target(self: portaldi::DI<Self>) -> portaldi::DI<dyn #target> {
+                    self
+                }
+            }
         });
+    }
+    Ok(quotes)
+}
 
-    if let Some(cap) = provider_target_cap {
-        let provide_target = ProvideTarget {
-            ident: quote::format_ident!("{}", &cap[1]),
-            generics: Generics_::default(),
-        };
-        build_provider(&ident, &provide_target, is_async, true, None)
-    } else {
-        quote! {}
+/// Tokens without spaces, e.g. `a::FooI<B>`.
+fn compact(tokens: impl ToTokens) -> String {
+    tokens.to_token_stream().to_string().replace(' ', "")
+}
+
+/// Build providers for each trait with bindings of their name, if any. (see `build_trait_providers`)
+/// With `default`, the providers are registered as `DIProvided` of the traits.
+pub fn build_named_trait_providers<'a>(
+    ident: &Ident,
+    args: &Generics_,
+    provide_targets: impl IntoIterator<Item = &'a ProvideTargets>,
+    mode: ResolveMode,
+) -> syn::Result<TokenStream> {
+    let provide_targets = provide_targets.into_iter().collect::<Vec<_>>();
+    let mut quotes = build_trait_providers(
+        ident,
+        args,
+        provide_targets.iter().flat_map(|t| &t.targets),
+        mode,
+    )?;
+    for ProvideTargets {
+        targets,
+        name,
+        default,
+    } in provide_targets
+    {
+        for target in targets {
+            if let Some(name) = name {
+                quotes.extend(build_named_binding(ident, target, name, mode)?);
+            }
+            if default.is_some() {
+                let provider_type = target.provider_ident()?;
+                quotes.extend(build_provided(target, &quote!(#provider_type)));
+            }
+        }
+    }
+    Ok(quotes)
+}
+
+/// Submit a named binding of a provider, which is collected at program start.
+/// The binding resolves the component without registrations of a container, which are per type, not per name.
+fn build_named_binding(
+    ident: &Ident,
+    provide_target: &ProvideTarget,
+    name: &LitStr,
+    mode: ResolveMode,
+) -> syn::Result<TokenStream> {
+    let provider_type = provide_target.provider_ident()?;
+    let (_, sync_body, async_body) = resolve_bodies(ident, mode);
+    let resolve_sync_quote = sync_body.map(|sync_body| {
+        quote! {
+            #[allow(unused_variables)]
+            fn resolve_named_sync(container: &portaldi::DIContainer, token: portaldi::SyncToken<Self::Mode>) -> portaldi::DI<Self::Output> {
+                #sync_body
+            }
+        }
+    });
+    Ok(quote! {
+        impl portaldi::NamedResolver for #provider_type {
+            #resolve_sync_quote
+            fn resolve_named_async(container: &portaldi::DIContainer) -> portaldi::BoxFuture<'_, portaldi::DI<Self::Output>> {
+                Box::pin(async move {
+                    let c: portaldi::DI<Self::Output> = #async_body;
+                    c
+                })
+            }
+        }
+
+        portaldi::inventory::submit! {
+            portaldi::NamedBinding::new::<#provider_type>(#name)
+        }
+    })
+}
+
+/// Build providers for traits named by provider patterns of the configuration.
+/// Patterns deriving the same target generate one provider.
+pub fn build_provider_by_pattern(
+    ident: &Ident,
+    args: &Generics_,
+    mode: ResolveMode,
+) -> syn::Result<TokenStream> {
+    let mut provider_types = HashSet::new();
+    let mut provide_targets = Vec::new();
+    for pattern in &config()?.provider_patterns {
+        if let Some(target) = pattern.target_of(ident, args)? {
+            if provider_types.insert(target.provider_ident()?.to_string()) {
+                provide_targets.push(target);
+            }
+        }
     }
+    build_trait_providers(ident, args, &provide_targets, mode)
 }
 
 pub fn async_trait_attr() -> proc_macro2::TokenStream {
-    if cfg!(feature = "multi-thread") {
+    if cfg!(feature = "native-async") {
+        // async traits are implemented by native `async fn`.
+        quote!()
+    } else if cfg!(feature = "multi-thread") {
         quote! {
-            #[async_trait::async_trait]
+            #[portaldi::async_trait]
         }
     } else {
         quote! {
-            #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
-            #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+            #[cfg_attr(target_arch = "wasm32", portaldi::async_trait(?Send))]
+            #[cfg_attr(not(target_arch = "wasm32"), portaldi::async_trait)]
+        }
+    }
+}
+
+/// `static_singleton` method of a portal, which caches a singleton in a static. (`static-singletons` feature)
+pub fn build_static_singleton(ty: &Type) -> TokenStream {
+    if cfg!(feature = "static-singletons") {
+        quote! {
+            fn static_singleton() -> Option<&'static portaldi::StaticSingleton<Self>> {
+                static SINGLETON: portaldi::StaticSingleton<#ty> = portaldi::StaticSingleton::new();
+                Some(&SINGLETON)
+            }
         }
+    } else {
+        quote!()
     }
 }
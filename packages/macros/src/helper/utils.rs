@@ -1,10 +1,13 @@
 use syn::Attribute;
 
-pub fn attr_of<'a>(attrs: &'a Vec<Attribute>, name: &str) -> Option<&'a Attribute> {
-    attrs.iter().find(|&a| {
-        a.path()
-            .get_ident()
-            .filter(|i| i.to_string() == name)
-            .is_some()
-    })
+pub fn attrs_of<'a>(attrs: &'a [Attribute], name: &'a str) -> impl Iterator<Item = &'a Attribute> {
+    attrs
+        .iter()
+        .filter(move |&a| a.path().get_ident().filter(|i| *i == name).is_some())
+}
+
+pub fn attr_of<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attrs
+        .iter()
+        .find(|&a| a.path().get_ident().filter(|i| *i == name).is_some())
 }
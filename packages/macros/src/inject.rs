@@ -0,0 +1,177 @@
+macro_rules! define {
+    () => {
+        /// Inject `DI<T>` parameters of a free function. (attribute macro)
+        ///
+        /// `DI<T>` parameters are resolved by the same rules as fields of `#[derive(DIPortal)]`,
+        /// and removed from the signature. In an async function, whether a component is created asynchronously is inferred
+        /// like fields, and async components are resolved concurrently. A sync function accepts only sync components.
+        /// Two functions are generated:
+        /// one resolves on the current container (see `with_container`), and the other (suffixed with `_on`) on a specified container.
+        /// Attributes of the function are put on the former, and the latter only inherits `cfg` and lint attributes,
+        /// which also apply to the body.
+        ///
+        /// ```ignore
+        /// #[portaldi::inject]
+        /// async fn run(
+        ///     repo: DI<dyn Repo>, // implicitly RepoProvider is used.
+        ///     clock: DI<Clock>,   // async creation is inferred.
+        ///     #[inject(TimerProvider, async)] timer: DI<Timer>, // a hand-written async provider.
+        ///     args: Args,
+        /// ) -> Result<()> {
+        ///     ...
+        /// }
+        ///
+        /// run(args).await;                 // on the current container.
+        /// run_on(&container, args).await;  // on a specified container.
+        /// ```
+        #[proc_macro_attribute]
+        pub fn inject(attr: TokenStream, item: TokenStream) -> TokenStream {
+            inject::exec(attr.into(), item.into()).into()
+        }
+    };
+}
+pub(crate) use define;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse2, parse_quote, punctuated::Punctuated, Attribute, FnArg, ItemFn, Token, Type};
+
+use crate::helper::{build_di_expr, build_resolver_exprs, config, parse_inject_attr};
+
+pub fn exec(attr: TokenStream2, item: TokenStream2) -> TokenStream2 {
+    expand(attr, item).unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "#[portaldi::inject] takes no arguments",
+        ));
+    }
+    let config = config()?;
+    let is_always_async = config.always_async;
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse2(item)?;
+
+    let mut inner_inputs = Punctuated::<FnArg, Token![,]>::new();
+    let mut outer_inputs = Punctuated::<FnArg, Token![,]>::new();
+    let mut outer_args = Vec::new();
+    let mut injections = Vec::new();
+    let mut async_injections = Vec::new();
+    for (i, input) in sig.inputs.iter().enumerate() {
+        let mut pat_type = match input {
+            FnArg::Typed(pat_type) => pat_type.clone(),
+            FnArg::Receiver(r) => {
+                return Err(syn::Error::new_spanned(
+                    r,
+                    "#[portaldi::inject] must be on a free function",
+                ))
+            }
+        };
+
+        let inject_attr = parse_inject_attr(&pat_type.attrs)?;
+        pat_type.attrs.retain(|a| !a.path().is_ident("inject"));
+        if inject_attr.is_none() && !is_di_type(&pat_type.ty) {
+            let arg = format_ident!("__arg{}", i);
+            let mut outer = pat_type.clone();
+            outer.pat = Box::new(parse_quote!(#arg));
+            outer_inputs.push(FnArg::Typed(outer));
+            outer_args.push(arg);
+            inner_inputs.push(FnArg::Typed(pat_type));
+            continue;
+        }
+
+        let is_async = is_always_async || inject_attr.as_ref().map(|a| a.is_async).unwrap_or(false);
+        if is_async && sig.asyncness.is_none() {
+            return Err(syn::Error::new_spanned(
+                &pat_type,
+                "async injection requires an async function",
+            ));
+        }
+        let inject_path = inject_attr.as_ref().and_then(|a| a.path.as_ref());
+        let (pat, ty) = (&pat_type.pat, &pat_type.ty);
+        if is_async {
+            let di_expr = build_di_expr(ty, inject_path, true)?;
+            async_injections.push((pat.clone(), ty.clone(), di_expr));
+        } else if inject_path.is_none() && sig.asyncness.is_some() {
+            // the mode is inferred, and a sync component is resolved without awaiting anything.
+            let async_expr = build_resolver_exprs(ty)?.async_expr;
+            async_injections.push((pat.clone(), ty.clone(), async_expr));
+        } else {
+            let di_expr = build_di_expr(ty, inject_path, false)?;
+            injections.push(quote!(let #pat: #ty = #di_expr;));
+        }
+    }
+
+    // async dependencies are resolved concurrently.
+    let async_injection_quote = match async_injections.as_slice() {
+        [] => quote!(),
+        [(pat, ty, expr)] => quote!(let #pat: #ty = #expr.await;),
+        _ => {
+            let pats = async_injections.iter().map(|(pat, ..)| pat);
+            let tys = async_injections.iter().map(|(_, ty, _)| ty);
+            let exprs = async_injections.iter().map(|(.., expr)| expr);
+            quote!(let (#(#pats),*): (#(#tys),*) = portaldi::join!(#(#exprs),*);)
+        }
+    };
+
+    let ident = &sig.ident;
+    let ident_on = format_ident!("{}_on", ident);
+    let await_ = sig.asyncness.map(|_| quote!(.await));
+
+    let mut outer_sig = sig.clone();
+    outer_sig.inputs = outer_inputs;
+
+    let mut inner_sig = sig.clone();
+    inner_sig.ident = ident_on.clone();
+    inner_sig.inputs = inner_inputs;
+    inner_sig
+        .inputs
+        .insert(0, parse_quote!(container: &portaldi::DIContainer));
+
+    let inner_attrs = attrs.iter().filter(|a| is_inherited(a));
+
+    let tracking_quote = config.tracking();
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #outer_sig {
+            #ident_on(&portaldi::DIContainer::current(), #(#outer_args),*)#await_
+        }
+
+        #[doc(hidden)]
+        #(#inner_attrs)*
+        #vis #inner_sig {
+            #(#injections)*
+            #async_injection_quote
+            #block
+        }
+
+        #tracking_quote
+    })
+}
+
+/// Whether an attribute is inherited by the `_on` function, i.e. `cfg` or a lint attribute.
+fn is_inherited(attr: &Attribute) -> bool {
+    ["cfg", "allow", "expect", "warn", "deny", "forbid"]
+        .iter()
+        .any(|name| attr.path().is_ident(name))
+}
+
+fn is_di_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => p
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident == "DI")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
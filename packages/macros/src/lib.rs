@@ -4,6 +4,9 @@ pub(crate) mod helper;
 
 use proc_macro::TokenStream;
 
+mod bind;
+bind::define!();
+
 mod def_async_di_provider;
 def_async_di_provider::define!();
 
@@ -16,5 +19,14 @@ derive_di_portal::define!();
 mod di;
 di::define!();
 
+mod inject;
+inject::define!();
+
+mod module;
+module::define!();
+
 mod provider;
 provider::define!();
+
+mod test;
+test::define!();
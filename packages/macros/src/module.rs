@@ -0,0 +1,210 @@
+macro_rules! define {
+    () => {
+        /// Generate a provider module, which re-exports providers and implements [`DIModule`].
+        ///
+        /// ```ignore
+        /// portaldi::module! {
+        ///     pub providers {
+        ///         dyn FooI from foo_impl,       // => pub use super::foo_impl::FooIProvider;
+        ///         dyn BarI<String> from bar,    // => pub use super::bar::BarI_GString_EProvider;
+        ///         Hoge from hoge,               // => pub use super::hoge::HogeProvider;
+        ///         dyn BazI,                     // => pub use super::BazIProvider;
+        ///         include crate::other::providers, // => pub use crate::other::providers::*;
+        ///     }
+        /// }
+        ///
+        /// use providers::*; // all providers are in this scope.
+        ///
+        /// // list bindings at runtime, including bindings of included modules.
+        /// providers::Module::bindings();
+        /// providers::Module::binding_of::<dyn FooI>();
+        /// ```
+        ///
+        /// Relative paths are resolved from the module where this macro is called.
+        #[proc_macro]
+        pub fn module(input: TokenStream) -> TokenStream {
+            module::exec(input.into()).into()
+        }
+    };
+}
+pub(crate) use define;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    parse2, parse_quote,
+    punctuated::Punctuated,
+    Attribute, Ident, Path, Token, Visibility,
+};
+
+use crate::helper::{kw, ProvideTarget};
+
+pub fn exec(input: TokenStream2) -> TokenStream2 {
+    expand(input).unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn expand(input: TokenStream2) -> syn::Result<TokenStream2> {
+    let ModuleInput {
+        attrs,
+        vis,
+        ident,
+        entries,
+    } = parse2(input)?;
+
+    let mut uses = Vec::new();
+    let mut bindings = Vec::new();
+    let mut includes = Vec::new();
+    for entry in entries {
+        match entry {
+            ModuleEntry::Binding {
+                kw_dyn,
+                target,
+                from,
+            } => {
+                let provider = target.provider_ident()?;
+                let target_name = type_name(quote!(#kw_dyn #target));
+                let provider_path = match from {
+                    Some(from) => rebase(&from),
+                    None => parse_quote!(super),
+                };
+                uses.push(quote!(pub use #provider_path::#provider;));
+                bindings.push(quote! {
+                    portaldi::Binding {
+                        target: #target_name,
+                        // the target is named through the provider, which is in scope of the module.
+                        target_id: std::any::TypeId::of::<<#provider as portaldi::DIResolver>::Output>(),
+                        provider: std::any::type_name::<#provider>(),
+                        module: module_path!(),
+                    }
+                });
+            }
+            ModuleEntry::Include(path) => includes.push(rebase(&path)),
+        }
+    }
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis mod #ident {
+            #(#uses)*
+            #(
+                #[allow(unused_imports)]
+                pub use #includes::*;
+            )*
+
+            /// Runtime handle of this provider module.
+            pub struct Module;
+
+            impl portaldi::DIModule for Module {
+                fn name() -> &'static str {
+                    module_path!()
+                }
+
+                fn bindings() -> Vec<portaldi::Binding> {
+                    #[allow(unused_mut)]
+                    let mut bindings = vec![#(#bindings),*];
+                    #(
+                        bindings.extend(<#includes::Module as portaldi::DIModule>::bindings());
+                    )*
+                    bindings
+                }
+            }
+        }
+    })
+}
+
+/// Format a type like `std::any::type_name`, e.g. `dyn FooI<String, (u8, bool)>`.
+fn type_name(ty: TokenStream2) -> String {
+    [
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        (" ::", "::"),
+        (":: ", "::"),
+        ("( ", "("),
+        (" )", ")"),
+        ("& ", "&"),
+        ("' ", "'"),
+    ]
+    .iter()
+    .fold(ty.to_string(), |s, (from, to)| s.replace(from, to))
+}
+
+/// Resolve a path from the inside of the generated module.
+fn rebase(path: &Path) -> Path {
+    let first = &path.segments[0].ident;
+    if path.leading_colon.is_some() || first == "crate" {
+        return path.clone();
+    }
+    let mut rebased: Path = parse_quote!(super);
+    let segments = path
+        .segments
+        .iter()
+        .skip(if first == "self" { 1 } else { 0 });
+    rebased.segments.extend(segments.cloned());
+    rebased
+}
+
+struct ModuleInput {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    ident: Ident,
+    entries: Punctuated<ModuleEntry, Token![,]>,
+}
+
+impl Parse for ModuleInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let ident = input.parse()?;
+        let content;
+        braced!(content in input);
+        let entries = content.parse_terminated(ModuleEntry::parse, Token![,])?;
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            entries,
+        })
+    }
+}
+
+enum ModuleEntry {
+    Binding {
+        kw_dyn: Option<Token![dyn]>,
+        target: ProvideTarget,
+        from: Option<Path>,
+    },
+    Include(Path),
+}
+
+impl Parse for ModuleEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(kw::include) {
+            let _: kw::include = input.parse()?;
+            return Ok(ModuleEntry::Include(Path::parse_mod_style(input)?));
+        }
+
+        let kw_dyn = input.parse()?;
+        let target: ProvideTarget = input.parse()?;
+        if target.path.segments.len() > 1 {
+            return Err(syn::Error::new_spanned(
+                &target.path,
+                "a binding target must be a type name; use `from` to specify its module",
+            ));
+        }
+        let from = if input.peek(kw::from) {
+            let _: kw::from = input.parse()?;
+            Some(Path::parse_mod_style(input)?)
+        } else {
+            None
+        };
+        Ok(ModuleEntry::Binding {
+            kw_dyn,
+            target,
+            from,
+        })
+    }
+}
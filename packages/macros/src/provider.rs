@@ -23,7 +23,39 @@ macro_rules! define {
         /// struct Hoge {}
         ///
         /// // When you needs manual creation logic, define DIPortal implementation.
-        /// #[portaldi::provider(HogeI<A>)] // HogeIAProvider will be generated.
+        /// #[portaldi::provider(HogeI<A>)] // HogeI_GA_EProvider will be generated.
+        /// impl DIPortal for Hoge {
+        ///   ...
+        /// }
+        /// ```
+        ///
+        /// For multiple traits, all providers share the same `Hoge` instance.
+        /// ```ignore
+        /// #[portaldi::provider(HogeI, PiyoI)] // HogeIProvider and PiyoIProvider will be generated.
+        /// impl DIPortal for Hoge {
+        ///   ...
+        /// }
+        /// ```
+        ///
+        /// A trait in another module can be specified by its path.
+        /// ```ignore
+        /// #[portaldi::provider(crate::service::HogeI)] // HogeIProvider will be generated here.
+        /// impl DIPortal for Hoge {
+        ///   ...
+        /// }
+        /// ```
+        ///
+        /// A `name` registers named bindings of the providers. (see `DIContainer::resolve_named`)
+        /// ```ignore
+        /// #[portaldi::provider(HogeI, name = "hoge")]
+        /// impl DIPortal for Hoge {
+        ///   ...
+        /// }
+        /// ```
+        ///
+        /// `default` registers the providers as the default ones of the traits. (see `ProviderOf`)
+        /// ```ignore
+        /// #[portaldi::provider(HogeI, default)]
         /// impl DIPortal for Hoge {
         ///   ...
         /// }
@@ -49,19 +81,25 @@ macro_rules! define {
 pub(crate) use define;
 
 use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
-    parse2, parse_quote, Ident, ImplItem, ItemImpl, Token, Type,
+    parse2, parse_quote, ImplItem, ItemImpl, Token, Type,
 };
 
-use crate::helper::{build_provider, build_provider_by_env, ProvideTarget};
+use crate::helper::{
+    build_named_trait_providers, build_provider, build_provider_by_pattern, build_static_singleton,
+    config, ProvideTarget, ProvideTargets, ResolveMode,
+};
 
 pub fn exec(attr: TokenStream2, item: TokenStream2) -> TokenStream2 {
-    let item_impl = parse2::<ItemImpl>(item.clone()).unwrap();
-    let args = parse2::<ProviderArgs>(attr).unwrap();
+    expand(attr, item).unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let mut item_impl = parse2::<ItemImpl>(item)?;
+    let args = parse2::<ProviderArgs>(attr)?;
 
-    // dbg!(&item_impl.trait_);
     let is_portal_impl = match &item_impl.trait_ {
         Some((_, p, _)) => p
             .segments
@@ -70,15 +108,28 @@ pub fn exec(attr: TokenStream2, item: TokenStream2) -> TokenStream2 {
         _ => false,
     };
     if !is_portal_impl {
-        panic!("[provider] must be on DIPortal or AsyncDIPortal")
+        let span_target: &dyn ToTokens = match &item_impl.trait_ {
+            Some((_, p, _)) => p,
+            None => &item_impl.self_ty,
+        };
+        return Err(syn::Error::new_spanned(
+            span_target,
+            "#[provider] must be on a `DIPortal` or `AsyncDIPortal` impl block",
+        ));
     }
 
-    // dbg!(&item_impl.self_ty);
     let (ident, path_args) = match *item_impl.self_ty {
-        Type::Path(ref p) => p.path.segments.last().map(|s| (&s.ident, &s.arguments)),
+        Type::Path(ref p) if p.qself.is_none() => {
+            p.path.segments.last().map(|s| (&s.ident, &s.arguments))
+        }
         _ => None,
     }
-    .expect("impl type name not found.");
+    .ok_or_else(|| {
+        syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "#[provider] requires a named type, e.g. `impl DIPortal for Foo`",
+        )
+    })?;
 
     let di_method = item_impl
         .items
@@ -87,59 +138,65 @@ pub fn exec(attr: TokenStream2, item: TokenStream2) -> TokenStream2 {
             ImplItem::Fn(m) if m.sig.ident == "create_for_di" => Some(m),
             _ => None,
         })
-        .expect("'di' method must be defined.");
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                &item_impl.self_ty,
+                "`create_for_di` must be defined in this impl block",
+            )
+        })?;
+
+    let mode = ResolveMode::from_async(di_method.sig.asyncness.is_some());
 
-    let is_async = di_method.sig.asyncness.is_some();
+    // a static can't be generic, so generic impls resolve on the container.
+    if item_impl.generics.params.is_empty() {
+        let static_singleton_quote = build_static_singleton(&item_impl.self_ty);
+        if !static_singleton_quote.is_empty() {
+            item_impl.items.push(parse_quote!(#static_singleton_quote));
+        }
+    }
 
     let provider_quote = match args {
-        ProviderArgs::TargetProvider(target) => {
-            build_provider(&ident, &target, is_async, true, None)
+        ProviderArgs::Targets(targets) => {
+            build_named_trait_providers(ident, &parse_quote!(#path_args), [&targets], mode)?
         }
-        ProviderArgs::EnvProvider => build_provider_by_env(&ident, is_async),
-        ProviderArgs::SelfProvider => build_provider(
-            &ident,
-            &ProvideTarget {
-                ident: ident.clone(),
-                generics: parse_quote!(#path_args),
-            },
-            is_async,
+        ProviderArgs::Pattern => build_provider_by_pattern(ident, &parse_quote!(#path_args), mode)?,
+        ProviderArgs::SelfType => build_provider(
+            ident,
+            &ProvideTarget::new(ident.clone(), parse_quote!(#path_args)),
+            mode,
             false,
             None,
-        ),
+        )?,
     };
 
-    // let q = quote! {
-    //     #item_impl
-    //     #provider_quote
-    // };
-    // println!("check !!!! {:}", q.to_string());
+    let tracking_quote = config()?.tracking();
 
-    quote! {
+    Ok(quote! {
         #item_impl
         #provider_quote
-    }
+        #tracking_quote
+    })
 }
 
 #[derive(Debug)]
 enum ProviderArgs {
-    SelfProvider,
-    TargetProvider(ProvideTarget),
-    EnvProvider,
+    SelfType,
+    Targets(ProvideTargets),
+    /// By provider patterns of the configuration.
+    Pattern,
 }
 
 impl Parse for ProviderArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         Ok(if input.peek(Token![Self]) {
             let _: Token![Self] = input.parse()?;
-            ProviderArgs::SelfProvider
+            ProviderArgs::SelfType
         } else {
-            let ident_: Option<Ident> = input.parse()?;
-            if let Some(ident) = ident_ {
-                let generics = input.parse()?;
-                ProviderArgs::TargetProvider(ProvideTarget { ident, generics })
+            if input.is_empty() {
+                ProviderArgs::Pattern
             } else {
-                ProviderArgs::EnvProvider
+                ProviderArgs::Targets(input.parse()?)
             }
         })
     }
-}
\ No newline at end of file
+}
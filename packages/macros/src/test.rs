@@ -0,0 +1,106 @@
+macro_rules! define {
+    () => {
+        /// Give a test its own global container. (attribute macro)
+        ///
+        /// `di` methods in the test resolve on a new container instead of the shared global container,
+        /// so tests can't leak components into each other.
+        ///
+        /// A sync function becomes a `#[test]`, and the container is replaced on the test thread while it runs. (see `isolate_global`)
+        /// For an async function, use it with a runtime's test attribute (`#[tokio::test]` or `#[actix_web::test]`) in either order.
+        /// The container is replaced on the test thread, and also for the test future wherever it is polled
+        /// when this attribute comes first. (see `replace_global_in`)
+        /// Tasks spawned on the test thread (e.g. on the current thread runtime) share the container,
+        /// but tasks on other threads (e.g. workers of a multi-thread runtime) and spawned threads use the shared global container
+        /// unless wrapped by `replace_global_in(DIContainer::global(), ..)`.
+        ///
+        /// ```ignore
+        /// #[portaldi::testing::test]
+        /// fn test_hoge() {
+        ///     let hoge = HogeService::di(); // resolved on the isolated container.
+        /// }
+        ///
+        /// #[portaldi::testing::test]
+        /// #[tokio::test(flavor = "multi_thread")]
+        /// async fn test_hoge_async() { ... }
+        /// ```
+        ///
+        /// It's exported only as `portaldi::testing::test`, so that `use portaldi::*` doesn't conflict with `#[test]`.
+        #[proc_macro_attribute]
+        pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+            test::exec(attr.into(), item.into()).into()
+        }
+    };
+}
+pub(crate) use define;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse2, parse_quote, Attribute, ItemFn, Path};
+
+pub fn exec(attr: TokenStream2, item: TokenStream2) -> TokenStream2 {
+    expand(attr, item).unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "#[portaldi::testing::test] takes no arguments",
+        ));
+    }
+
+    let mut item_fn: ItemFn = parse2(item)?;
+
+    let test_attr = item_fn.attrs.iter().find_map(known_test_attr);
+    if item_fn.sig.asyncness.is_none() {
+        if test_attr.is_none() {
+            item_fn
+                .attrs
+                .push(parse_quote!(#[::core::prelude::v1::test]));
+        }
+        let guard = parse_quote!(let __portaldi_guard = portaldi::isolate_global(););
+        item_fn.block.stmts.insert(0, guard);
+        return Ok(quote!(#item_fn));
+    }
+
+    if !matches!(test_attr, Some(TestAttr::Runtime)) {
+        return Err(syn::Error::new_spanned(
+            item_fn.sig.asyncness,
+            "async test requires a runtime's test attribute, e.g. #[tokio::test]",
+        ));
+    }
+
+    // the same container on the test thread (for tasks spawned on it) and for the test future.
+    let block = &item_fn.block;
+    item_fn.block = parse_quote!({
+        let __portaldi_global = portaldi::DI::new(portaldi::DIContainer::new());
+        let __portaldi_guard = portaldi::replace_global(__portaldi_global.clone());
+        portaldi::replace_global_in(__portaldi_global, async move #block).await
+    });
+    Ok(quote!(#item_fn))
+}
+
+enum TestAttr {
+    Builtin,
+    Runtime,
+}
+
+/// Kind of the attribute if it is a known test attribute.
+///
+/// The builtin attribute is also matched by its absolute path, which runtimes' test attributes expand to.
+fn known_test_attr(attr: &Attribute) -> Option<TestAttr> {
+    let path = path_string(attr.path());
+    match path.as_str() {
+        "test" | "core::prelude::v1::test" | "std::prelude::v1::test" => Some(TestAttr::Builtin),
+        "tokio::test" | "actix_web::test" => Some(TestAttr::Runtime),
+        _ => None,
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
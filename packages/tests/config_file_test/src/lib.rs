@@ -0,0 +1,140 @@
+use portaldi::*;
+
+pub struct User;
+pub struct Order;
+
+pub trait Repo<T>: DITarget {}
+
+// provides `dyn Repo<User>` by a pattern.
+#[derive(DIPortal)]
+pub struct SqlUserRepo {}
+impl Repo<User> for SqlUserRepo {}
+
+// provides `dyn Repo<Order>` by a pattern.
+#[derive(DIPortal)]
+pub struct SqlOrderRepo {}
+impl Repo<Order> for SqlOrderRepo {}
+
+pub trait Clock: DITarget {}
+
+// provides `dyn Clock` by a pattern.
+#[derive(DIPortal)]
+#[singleton]
+pub struct ClockImpl {}
+impl Clock for ClockImpl {}
+
+// providers are `pub(crate)`, so they can provide a crate private trait.
+pub(crate) trait Mailer: DITarget {}
+
+#[derive(DIPortal)]
+pub struct MailerImpl {}
+impl Mailer for MailerImpl {}
+
+pub trait Audit: DITarget {}
+
+// provides `dyn Audit` by another pattern.
+#[derive(DIPortal)]
+pub struct PostgresAudit {}
+impl Audit for PostgresAudit {}
+
+pub trait Cache<T>: DITarget {}
+
+pub struct CacheImpl<T>(std::marker::PhantomData<T>);
+impl Cache<String> for CacheImpl<String> {}
+
+// provides `dyn Cache<String>` by a pattern with type arguments of the type.
+#[provider]
+impl DIPortal for CacheImpl<String> {
+    fn create_for_di(_container: &DIContainer) -> Self {
+        CacheImpl(std::marker::PhantomData)
+    }
+}
+
+// opts out of patterns, so `FooProvider` below is not duplicated.
+#[derive(DIPortal)]
+#[provide(none)]
+pub struct FooImpl {}
+
+pub trait Foo: DITarget {}
+impl Foo for FooImpl {}
+
+def_di_provider!(dyn Foo, |_| FooImpl {});
+
+pub trait Store: DITarget {
+    fn name(&self) -> &str;
+}
+
+// `dyn Store` is provided by patterns in sibling modules, which are chosen by importing.
+pub mod postgres {
+    use super::Store;
+    use portaldi::*;
+
+    #[derive(DIPortal)]
+    pub struct PostgresStore {}
+    impl Store for PostgresStore {
+        fn name(&self) -> &str {
+            "postgres"
+        }
+    }
+}
+
+pub mod memory {
+    use super::Store;
+    use portaldi::*;
+
+    #[derive(DIPortal)]
+    pub struct StoreImpl {}
+    impl Store for StoreImpl {
+        fn name(&self) -> &str {
+            "memory"
+        }
+    }
+}
+
+pub mod app {
+    use super::memory::StoreProvider;
+    use super::Store;
+    use portaldi::*;
+
+    #[derive(DIPortal)]
+    pub struct App {
+        pub store: DI<dyn Store>,
+    }
+}
+
+// scoped by default.
+#[derive(DIPortal)]
+pub struct Hoge {
+    pub user_repo: DI<dyn Repo<User>>,
+    pub order_repo: DI<dyn Repo<Order>>,
+    pub clock: DI<dyn Clock>,
+    pub audit: DI<dyn Audit>,
+    pub cache: DI<dyn Cache<String>>,
+    pub foo: DI<dyn Foo>,
+    _mailer: DI<dyn Mailer>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() {
+        let c = DI::new(DIContainer::new());
+        let (s1, s2) = (c.scope(), c.scope());
+        let (hoge1, hoge2) = (Hoge::di_on(&s1), Hoge::di_on(&s2));
+        assert!(!DI::ptr_eq(&hoge1, &hoge2));
+        assert!(DI::ptr_eq(&hoge1, &Hoge::di_on(&s1)));
+        assert!(std::ptr::addr_eq(
+            DI::as_ptr(&hoge1.clock),
+            DI::as_ptr(&hoge2.clock)
+        ));
+    }
+
+    #[test]
+    fn test_patterns_in_sibling_modules() {
+        let c = DIContainer::new();
+        assert_eq!(app::App::di_on(&c).store.name(), "memory");
+        assert_eq!(postgres::StoreProvider::di_on(&c).name(), "postgres");
+    }
+}
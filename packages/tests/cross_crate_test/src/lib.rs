@@ -0,0 +1,149 @@
+//! Providers of traits defined in another crate.
+
+/// A trait specified by its path.
+pub mod by_path {
+    use portaldi::*;
+
+    #[derive(DIPortal)]
+    #[provide(traits::FooI)]
+    pub struct Foo;
+    impl traits::FooI for Foo {
+        fn name(&self) -> &str {
+            "by_path"
+        }
+    }
+}
+
+/// An imported trait, provided by a sibling of `by_path`.
+pub mod by_import {
+    use portaldi::*;
+    use traits::FooI;
+
+    #[derive(DIPortal)]
+    #[provide(FooI)]
+    pub struct Foo;
+    impl FooI for Foo {
+        fn name(&self) -> &str {
+            "by_import"
+        }
+    }
+}
+
+pub mod by_def {
+    use portaldi::*;
+
+    pub struct Bar;
+    impl traits::BarI for Bar {
+        fn name(&self) -> &str {
+            "by_def"
+        }
+    }
+    def_di_provider!(dyn traits::BarI, |_| Bar);
+}
+
+pub mod by_async_def {
+    use portaldi::*;
+
+    pub struct Bar;
+    impl traits::BarI for Bar {
+        fn name(&self) -> &str {
+            "by_async_def"
+        }
+    }
+    def_async_di_provider!(dyn traits::BarI, |_| async { Bar });
+}
+
+/// A trait bound in a composition root.
+pub mod by_bind {
+    use portaldi::*;
+
+    #[derive(DIPortal)]
+    pub struct Baz;
+    impl traits::BazI for Baz {
+        fn name(&self) -> &str {
+            "by_bind"
+        }
+    }
+    bind!(dyn traits::BazI => Baz);
+
+    /// A trait of this crate bound to a type of another crate.
+    pub trait ClockI: DITarget {}
+    impl ClockI for traits::Clock {}
+    bind!(dyn ClockI => traits::Clock);
+}
+
+/// A trait provided by a pattern of the configuration.
+pub mod by_pattern {
+    use portaldi::*;
+    use traits::QuxI;
+
+    #[derive(DIPortal)]
+    pub struct QuxIImpl;
+    impl QuxI for QuxIImpl {
+        fn name(&self) -> &str {
+            "by_pattern"
+        }
+    }
+}
+
+pub mod app {
+    use super::by_bind::{BazIProvider, ClockI, ClockIProvider};
+    use super::by_def::BarIProvider;
+    use super::by_path::FooIProvider;
+    use super::by_pattern::QuxIProvider;
+    use portaldi::*;
+    use traits::{BarI, BazI, FooI, QuxI};
+
+    #[derive(DIPortal)]
+    pub struct App {
+        pub foo: DI<dyn FooI>,
+        pub bar: DI<dyn BarI>,
+        pub baz: DI<dyn BazI>,
+        pub clock: DI<dyn ClockI>,
+        pub qux: DI<dyn QuxI>,
+    }
+}
+
+pub mod async_app {
+    use super::by_async_def::BarIProvider;
+    use super::by_import::FooIProvider;
+    use portaldi::*;
+    use traits::{BarI, FooI};
+
+    #[derive(DIPortal)]
+    pub struct App {
+        pub foo: DI<dyn FooI>,
+        pub bar: DI<dyn BarI>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use portaldi::*;
+
+    #[test]
+    fn test_di() {
+        let app = app::App::di();
+        assert_eq!(app.foo.name(), "by_path");
+        assert_eq!(app.bar.name(), "by_def");
+        assert!(DI::ptr_eq(&app.foo, &by_path::FooIProvider::di()));
+        assert!(DI::ptr_eq(&app.bar, &by_def::BarIProvider::di()));
+        assert_eq!(app.baz.name(), "by_bind");
+        assert!(DI::ptr_eq(&app.baz, &by_bind::BazIProvider::di()));
+        assert!(DI::ptr_eq(&app.clock, &by_bind::ClockIProvider::di()));
+        assert_eq!(app.qux.name(), "by_pattern");
+    }
+
+    #[tokio::test]
+    async fn test_async_di() {
+        let app = async_app::App::di().await;
+        assert_eq!(app.foo.name(), "by_import");
+        assert_eq!(app.bar.name(), "by_async_def");
+        assert!(DI::ptr_eq(&app.foo, &by_import::FooIProvider::di()));
+        assert!(DI::ptr_eq(
+            &app.bar,
+            &by_async_def::BarIProvider::di().await
+        ));
+    }
+}
@@ -0,0 +1,23 @@
+//! Traits provided by `cross_crate_test`.
+
+use portaldi::DITarget;
+
+pub trait FooI: DITarget {
+    fn name(&self) -> &str;
+}
+
+pub trait BarI: DITarget {
+    fn name(&self) -> &str;
+}
+
+pub trait BazI: DITarget {
+    fn name(&self) -> &str;
+}
+
+/// A type of this crate, which is bound to a trait of `cross_crate_test`.
+#[derive(portaldi::DIPortal)]
+pub struct Clock;
+
+pub trait QuxI: DITarget {
+    fn name(&self) -> &str;
+}
@@ -0,0 +1,130 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use portaldi::*;
+
+pub trait GreeterI: DITarget {
+    fn greet(&self) -> String;
+}
+
+#[derive(DIPortal)]
+#[provide(GreeterI)]
+pub struct Greeter {}
+
+impl GreeterI for Greeter {
+    fn greet(&self) -> String {
+        "hello".to_string()
+    }
+}
+
+static REQUEST_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// A component created per request.
+#[derive(Debug)]
+pub struct RequestId(pub usize);
+
+#[provider(Self)]
+impl DIPortal for RequestId {
+    const SCOPED: bool = true;
+
+    fn create_for_di(_container: &DIContainer) -> Self {
+        RequestId(REQUEST_COUNT.fetch_add(1, Ordering::SeqCst))
+    }
+}
+
+pub struct AsyncFoo {}
+
+#[provider(Self)]
+#[async_trait::async_trait]
+impl AsyncDIPortal for AsyncFoo {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        AsyncFoo {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpRequest};
+    use portaldi::integration::actix::{request_scope, AsyncInject, Inject};
+    use std::sync::Mutex;
+
+    async fn handler(
+        greeter: Inject<GreeterIProvider>,
+        id1: Inject<RequestIdProvider>,
+        id2: Inject<RequestIdProvider>,
+        _foo: AsyncInject<AsyncFooProvider>,
+    ) -> String {
+        assert!(std::ptr::eq(id1.as_ref(), id2.as_ref()));
+        format!("{} {}", greeter.greet(), id1.0 .0)
+    }
+
+    fn request_id(body: &[u8]) -> usize {
+        let body = std::str::from_utf8(body).unwrap();
+        body.split(' ').nth(1).unwrap().parse().unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_inject_on_app_data_container() {
+        let container = DI::new(DIContainer::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(container.clone()))
+                .route("/", web::get().to(handler)),
+        )
+        .await;
+
+        let body1 = test::call_and_read_body(&app, test::TestRequest::get().to_request()).await;
+        let body2 = test::call_and_read_body(&app, test::TestRequest::get().to_request()).await;
+        assert!(body1.starts_with(b"hello "));
+        // request scoped components are created per request.
+        assert_ne!(request_id(&body1), request_id(&body2));
+        // singletons are in the application container.
+        assert!(container.get::<Greeter>().is_some());
+        assert!(container.get::<RequestId>().is_none());
+    }
+
+    #[actix_web::test]
+    async fn test_inject_on_global_container() {
+        let app = test::init_service(App::new().route("/", web::get().to(handler))).await;
+        let body = test::call_and_read_body(&app, test::TestRequest::get().to_request()).await;
+        assert!(body.starts_with(b"hello "));
+        assert!(DIContainer::global().get::<Greeter>().is_some());
+    }
+
+    #[portaldi::testing::test]
+    #[actix_web::test]
+    async fn test_inject_on_isolated_global_container() {
+        let app = test::init_service(App::new().route("/", web::get().to(handler))).await;
+        test::call_and_read_body(&app, test::TestRequest::get().to_request()).await;
+        assert!(DIContainer::global().get::<Greeter>().is_some());
+        // the shared global container is used on other threads.
+        let shared = std::thread::spawn(DIContainer::global).join().unwrap();
+        assert!(!DI::ptr_eq(&shared, &DIContainer::global()));
+    }
+
+    type Scopes = Mutex<Vec<DI<DIContainer>>>;
+
+    async fn capture_scope(
+        req: HttpRequest,
+        scopes: web::Data<Scopes>,
+        _id: Inject<RequestIdProvider>,
+    ) -> String {
+        scopes.lock().unwrap().push(request_scope(&req));
+        String::new()
+    }
+
+    #[actix_web::test]
+    async fn test_scope_disposed_with_request() {
+        let scopes = web::Data::new(Scopes::default());
+        let app = test::init_service(
+            App::new()
+                .app_data(scopes.clone())
+                .route("/", web::get().to(capture_scope)),
+        )
+        .await;
+        test::call_service(&app, test::TestRequest::get().to_request()).await;
+
+        let scope = scopes.lock().unwrap().pop().unwrap();
+        assert!(scope.get::<RequestId>().is_none());
+    }
+}
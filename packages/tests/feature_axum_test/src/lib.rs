@@ -0,0 +1,148 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use portaldi::*;
+
+pub trait GreeterI: DITarget {
+    fn greet(&self) -> String;
+}
+
+#[derive(DIPortal)]
+#[provide(GreeterI)]
+pub struct Greeter {}
+
+impl GreeterI for Greeter {
+    fn greet(&self) -> String {
+        "hello".to_string()
+    }
+}
+
+static REQUEST_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// A component created per request.
+#[derive(Debug)]
+pub struct RequestId(pub usize);
+
+#[provider(Self)]
+impl DIPortal for RequestId {
+    const SCOPED: bool = true;
+
+    fn create_for_di(_container: &DIContainer) -> Self {
+        RequestId(REQUEST_COUNT.fetch_add(1, Ordering::SeqCst))
+    }
+}
+
+pub struct AsyncFoo {}
+
+#[provider(Self)]
+#[async_trait::async_trait]
+impl AsyncDIPortal for AsyncFoo {
+    async fn create_for_di(_container: &DIContainer) -> Self {
+        AsyncFoo {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::FromRef, http::Request, routing::get, Router};
+    use http_body_util::BodyExt;
+    use portaldi::integration::axum::{AsyncInject, DIState, Inject};
+    use portaldi::integration::tower::DIScopeLayer;
+    use tower::ServiceExt;
+
+    async fn handler(
+        Inject(greeter): Inject<GreeterIProvider>,
+        Inject(id1): Inject<RequestIdProvider>,
+        Inject(id2): Inject<RequestIdProvider>,
+        AsyncInject(_foo): AsyncInject<AsyncFooProvider>,
+    ) -> String {
+        assert!(std::ptr::eq(id1.as_ref(), id2.as_ref()));
+        format!("{} {}", greeter.greet(), id1.0)
+    }
+
+    async fn call(router: Router) -> String {
+        let response = router
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    fn request_id(body: &str) -> usize {
+        body.split(' ').nth(1).unwrap().parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_inject_on_global_container() {
+        let router = Router::new().route("/", get(handler));
+        let body1 = call(router.clone()).await;
+        let body2 = call(router).await;
+        assert!(body1.starts_with("hello "));
+        // request scoped components are created per request.
+        assert_ne!(request_id(&body1), request_id(&body2));
+    }
+
+    #[derive(Clone)]
+    struct AppState {
+        container: DI<DIContainer>,
+    }
+
+    impl FromRef<AppState> for DIState {
+        fn from_ref(state: &AppState) -> Self {
+            DIState(state.container.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inject_on_state_container() {
+        let container = DI::new(DIContainer::new());
+        let router = Router::new()
+            .route("/", get(handler))
+            .with_state(AppState {
+                container: container.clone(),
+            });
+        call(router).await;
+        // singletons are in the application container.
+        assert!(container.get::<Greeter>().is_some());
+        assert!(container.get::<RequestId>().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_inject_on_extension_container() {
+        let container = DI::new(DIContainer::new());
+        let scope = DI::new(container.scope());
+        let router = Router::new().route("/", get(handler));
+        let request = Request::get("/")
+            .extension(scope.clone())
+            .body(Body::empty())
+            .unwrap();
+        router.oneshot(request).await.unwrap();
+        assert!(scope.get::<RequestId>().is_some());
+        assert!(container.get::<Greeter>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_inject_on_layer_scope() {
+        let container = DI::new(DIContainer::new());
+        let router = Router::new()
+            .route("/", get(handler))
+            .layer(DIScopeLayer::new(container.clone()));
+        let body1 = call(router.clone()).await;
+        let body2 = call(router).await;
+        assert_ne!(request_id(&body1), request_id(&body2));
+        assert!(container.get::<Greeter>().is_some());
+        assert!(container.get::<RequestId>().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_inject_on_current_container() {
+        let container = DI::new(DIContainer::new());
+        // neither state nor a layer.
+        let router = Router::new().route("/", get(handler));
+        let body = with_container(&container, call(router)).await;
+        assert!(body.starts_with("hello "));
+        assert!(container.get::<Greeter>().is_some());
+        assert!(container.get::<RequestId>().is_none());
+    }
+}
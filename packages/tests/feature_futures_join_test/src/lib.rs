@@ -1,3 +1,7 @@
+//! `futures` dependency is not required, joins are done by `portaldi::join!`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use portaldi::*;
 
 #[derive(DIPortal, Debug)]
@@ -43,6 +47,33 @@ impl AsyncDIPortal for Bar {
 #[derive(DIPortal, Debug)]
 pub struct Baz {}
 
+static SHARED_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+pub struct Shared {}
+
+#[provider(Self)]
+#[async_trait::async_trait]
+impl AsyncDIPortal for Shared {
+    async fn create_for_di(_c: &DIContainer) -> Self {
+        SHARED_CREATED.fetch_add(1, Ordering::SeqCst);
+        tokio::task::yield_now().await;
+        Shared {}
+    }
+}
+
+/// Depends on Shared both directly and through Piyo2, which are resolved concurrently.
+#[derive(DIPortal, Debug)]
+pub struct Piyo {
+    pub shared: DI<Shared>,
+    pub piyo2: DI<Piyo2>,
+}
+
+#[derive(DIPortal, Debug)]
+pub struct Piyo2 {
+    pub shared: DI<Shared>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,4 +84,11 @@ mod tests {
         println!("{:?}", Hoge2::di().await);
         println!("{:?}", Hoge3::di());
     }
+
+    #[tokio::test]
+    async fn test_single_flight() {
+        let piyo = Piyo::di().await;
+        assert!(DI::ptr_eq(&piyo.shared, &piyo.piyo2.shared));
+        assert_eq!(SHARED_CREATED.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,69 @@
+//! No `async-trait` dependency is required with `native-async` feature.
+
+use portaldi::*;
+
+#[derive(DIPortal)]
+pub struct Hoge {
+    #[inject(async)]
+    pub foo: DI<Foo>,
+    #[inject(async)]
+    pub bar: DI<dyn BarI>,
+}
+
+pub struct Foo {}
+
+#[provider(Self)]
+impl AsyncDIPortal for Foo {
+    async fn create_for_di(_c: &DIContainer) -> Self {
+        std::future::ready(()).await;
+        Foo {}
+    }
+}
+
+pub trait BarI: DITarget {}
+
+#[derive(DIPortal)]
+#[provide(BarI)]
+pub struct Bar {
+    #[inject(async)]
+    _foo: DI<Foo>,
+}
+
+impl BarI for Bar {}
+
+pub struct Baz {}
+
+#[provider(Self)]
+impl AsyncDIPortal for Baz {
+    async fn create_for_di(_c: &DIContainer) -> Self {
+        Baz {}
+    }
+}
+
+pub trait BazI: DITarget {}
+impl BazI for Baz {}
+
+bind!(dyn BazI => async Baz);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_async() {
+        let hoge = Hoge::di().await;
+        assert!(DI::ptr_eq(&hoge.foo, &Foo::di().await));
+        assert!(DI::ptr_eq(&hoge.bar, &BarIProvider::di().await));
+        BazIProvider::di().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_future() {
+        // futures of native async fns are Send.
+        let c = DI::new(DIContainer::new());
+        let hoge = tokio::spawn(async move { Hoge::di_on(&c).await })
+            .await
+            .unwrap();
+        assert!(!DI::ptr_eq(&hoge, &Hoge::di().await));
+    }
+}
@@ -0,0 +1,163 @@
+//! Singletons on the global container are cached in per-type statics with `static-singletons` feature.
+
+use portaldi::*;
+
+#[derive(DIPortal)]
+#[provide(HogeI)]
+pub struct Hoge {
+    pub foo: DI<Foo>,
+}
+
+pub trait HogeI: DITarget {}
+impl HogeI for Hoge {}
+
+pub struct Foo {}
+
+#[provider(Self)]
+impl DIPortal for Foo {
+    fn create_for_di(_c: &DIContainer) -> Self {
+        Foo {}
+    }
+}
+
+#[derive(DIPortal)]
+pub struct Piyo {
+    pub bar: DI<Bar>,
+}
+
+pub struct Bar {}
+
+#[provider(Self)]
+#[async_trait]
+impl AsyncDIPortal for Bar {
+    async fn create_for_di(_c: &DIContainer) -> Self {
+        Bar {}
+    }
+}
+
+#[derive(DIPortal)]
+#[scoped]
+pub struct Scoped {}
+
+pub struct Generic<T>(std::marker::PhantomData<T>);
+
+#[provider(Self)]
+impl DIPortal for Generic<Foo> {
+    fn create_for_di(_c: &DIContainer) -> Self {
+        Generic(std::marker::PhantomData)
+    }
+}
+
+pub struct Config {
+    pub name: &'static str,
+}
+
+#[provider(Self)]
+impl DIPortal for Config {
+    fn create_for_di(_c: &DIContainer) -> Self {
+        Config { name: "default" }
+    }
+}
+
+pub struct AsyncConfig {
+    pub name: &'static str,
+}
+
+#[provider(Self)]
+#[async_trait]
+impl AsyncDIPortal for AsyncConfig {
+    async fn create_for_di(_c: &DIContainer) -> Self {
+        AsyncConfig { name: "default" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global() {
+        let hoge = Hoge::di();
+        assert!(<Hoge as DIPortal>::static_singleton().is_some());
+        assert!(DI::ptr_eq(&hoge, &Hoge::di()));
+        assert!(DI::ptr_eq(&hoge, &Hoge::di_on(&DIContainer::global())));
+        assert!(DI::ptr_eq(&hoge, &HogeProvider::di()));
+        assert!(DI::ptr_eq(&hoge.foo, &Foo::di()));
+        assert!(DI::ptr_eq(&hoge.foo, &Foo::di_on(&DIContainer::global())));
+
+        let hoge_i: DI<dyn HogeI> = HogeIProvider::di();
+        assert!(std::ptr::addr_eq(DI::as_ptr(&hoge_i), DI::as_ptr(&hoge)));
+    }
+
+    #[test]
+    fn test_global_initialized_by_container() {
+        // an instance created through the container is cached by di().
+        let generic = Generic::<Foo>::di_on(&DIContainer::global());
+        assert!(DI::ptr_eq(&generic, &Generic::<Foo>::di()));
+    }
+
+    #[tokio::test]
+    async fn test_global_async() {
+        let piyo = Piyo::di().await;
+        assert!(DI::ptr_eq(&piyo, &Piyo::di().await));
+        assert!(DI::ptr_eq(&piyo, &Piyo::di_on(&DIContainer::global()).await));
+        assert!(DI::ptr_eq(&piyo.bar, &Bar::di().await));
+        assert!(DI::ptr_eq(&piyo.bar, &Bar::di_on(&DIContainer::global()).await));
+    }
+
+    #[tokio::test]
+    async fn test_other_containers() {
+        let hoge = Hoge::di();
+
+        let c = DI::new(DIContainer::new());
+        let hoge2 = with_container(&c, async { Hoge::di() }).await;
+        assert!(!DI::ptr_eq(&hoge, &hoge2));
+        assert!(DI::ptr_eq(&hoge2, &Hoge::di_on(&c)));
+
+        {
+            let _guard = isolate_global();
+            let hoge3 = Hoge::di();
+            assert!(!DI::ptr_eq(&hoge, &hoge3));
+            assert!(DI::ptr_eq(&hoge3, &Hoge::di_on(&DIContainer::global())));
+        }
+        assert!(DI::ptr_eq(&hoge, &Hoge::di()));
+    }
+
+    #[test]
+    fn test_scoped() {
+        // scoped components are not cached.
+        let scope = DI::new(DIContainer::global().scope());
+        let scoped = with_container_sync(&scope, Scoped::di);
+        assert!(DI::ptr_eq(&scoped, &Scoped::di_on(&scope)));
+        assert!(!DI::ptr_eq(&scoped, &Scoped::di()));
+    }
+
+    #[test]
+    fn test_register() {
+        let config = Config::di();
+        assert_eq!(config.name, "default");
+        assert!(DI::ptr_eq(&config, &Config::di()));
+
+        // a registration on the global container invalidates the cached instance.
+        DIContainer::global().register::<Config>(|_| DI::new(Config { name: "registered" }));
+        let registered = Config::di();
+        assert_eq!(registered.name, "registered");
+        assert!(DI::ptr_eq(&registered, &Config::di()));
+        assert!(DI::ptr_eq(&registered, &ConfigProvider::di()));
+    }
+
+    #[tokio::test]
+    async fn test_register_async() {
+        let config = AsyncConfig::di().await;
+        assert_eq!(config.name, "default");
+        assert!(DI::ptr_eq(&config, &AsyncConfig::di().await));
+
+        DIContainer::global().register_async::<AsyncConfig>(|_| {
+            Box::pin(async { DI::new(AsyncConfig { name: "registered" }) })
+        });
+        let registered = AsyncConfig::di().await;
+        assert_eq!(registered.name, "registered");
+        assert!(DI::ptr_eq(&registered, &AsyncConfig::di().await));
+        assert!(DI::ptr_eq(&registered, &AsyncConfigProvider::di().await));
+    }
+}
@@ -0,0 +1,27 @@
+//! Disposal of the global container, in its own test binary so that other tests don't share the global container.
+
+use feature_static_singletons_test::*;
+use portaldi::*;
+
+#[test]
+fn test_dispose() {
+    let hoge = Hoge::di();
+    assert!(DI::ptr_eq(&hoge, &Hoge::di()));
+
+    // dispose of the global container invalidates cached instances.
+    DIContainer::global().dispose();
+    let hoge2 = Hoge::di();
+    assert!(!DI::ptr_eq(&hoge, &hoge2));
+    assert!(!DI::ptr_eq(&hoge.foo, &Foo::di()));
+    assert!(DI::ptr_eq(&hoge2, &Hoge::di_on(&DIContainer::global())));
+
+    // cached again after the first cached instance is invalidated.
+    assert!(DI::ptr_eq(
+        &<Hoge as DIPortal>::static_singleton()
+            .unwrap()
+            .get()
+            .unwrap(),
+        &hoge2
+    ));
+    assert!(DI::ptr_eq(&hoge2, &Hoge::di()));
+}
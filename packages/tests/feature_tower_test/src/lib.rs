@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use portaldi::*;
+
+pub static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// A component created per request.
+#[derive(DIPortal)]
+#[scoped]
+pub struct RequestContext {
+    pub config: DI<Config>,
+}
+
+impl Drop for RequestContext {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(DIPortal)]
+pub struct Config {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{Request, Response};
+    use portaldi::integration::tower::DIScopeLayer;
+    use std::{convert::Infallible, time::Duration};
+    use tower::{service_fn, Layer, Service, ServiceExt};
+
+    type Captured = (DI<DIContainer>, usize);
+
+    async fn handle(req: Request<()>) -> Result<Response<Captured>, Infallible> {
+        let scope = req.extensions().get::<DI<DIContainer>>().unwrap().clone();
+        let ctx = RequestContext::di_on(&scope);
+        let ctx_ptr = DI::as_ptr(&ctx) as usize;
+        Ok(Response::new((scope, ctx_ptr)))
+    }
+
+    #[tokio::test]
+    async fn test_scope_per_request() {
+        let container = DI::new(DIContainer::new());
+        let service = DIScopeLayer::new(container.clone()).layer(service_fn(handle));
+
+        let dropped = DROPPED.load(Ordering::SeqCst);
+        let res1 = service.clone().oneshot(Request::new(())).await.unwrap();
+        let res2 = service.oneshot(Request::new(())).await.unwrap();
+        let (scope1, ctx1) = res1.into_body();
+        let (scope2, ctx2) = res2.into_body();
+
+        // each request has its own scope of the application container.
+        assert!(!DI::ptr_eq(&scope1, &scope2));
+        assert!(std::ptr::eq(scope1.root(), container.as_ref()));
+        assert_ne!(ctx1, ctx2);
+        // singletons are shared.
+        assert!(container.get::<Config>().is_some());
+
+        // scopes are disposed when responses complete.
+        assert!(scope1.get::<RequestContext>().is_none());
+        assert!(scope2.get::<RequestContext>().is_none());
+        assert!(DROPPED.load(Ordering::SeqCst) >= dropped + 2);
+    }
+
+    #[tokio::test]
+    async fn test_scope_disposed_on_cancel() {
+        let container = DI::new(DIContainer::new());
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let mut tx = Some(tx);
+        let mut service = DIScopeLayer::new(container).layer(service_fn(move |req: Request<()>| {
+            let scope = req.extensions().get::<DI<DIContainer>>().unwrap().clone();
+            RequestContext::di_on(&scope);
+            tx.take().unwrap().send(scope).unwrap();
+            async { std::future::pending::<Result<Response<()>, Infallible>>().await }
+        }));
+
+        let future = service.ready().await.unwrap().call(Request::new(()));
+        // the request is cancelled before the response.
+        let result = tokio::time::timeout(Duration::from_millis(10), future).await;
+        assert!(result.is_err());
+
+        let scope = rx.await.unwrap();
+        assert!(scope.get::<RequestContext>().is_none());
+    }
+}